@@ -1,15 +1,57 @@
-use macroquad::prelude::{load_image, Color, Texture2D, WHITE};
+use crate::game::Profile;
+use macroquad::{
+    audio::{load_sound, Sound},
+    prelude::{load_image, load_string, Color, FilterMode, Texture2D, WHITE},
+};
+use std::collections::HashMap;
+
+/// The characters in `people/` that the farmer can meet
+const PEOPLE: &[&str] = &["greg"];
+/// The characters with a picture in `assets/portraits/`
+const PORTRAITS: &[&str] = &["greg", "trader"];
 
 pub struct Assets {
     pub farmer_front: Texture2D,
     pub farm: Texture2D,
+    /// Played as the diary text appears
+    pub pencil: Sound,
+    pub people: Vec<Profile>,
+    portraits: HashMap<&'static str, Texture2D>,
 }
 
 impl Assets {
     pub async fn new() -> Self {
         let farmer_front = load_image_transparent_color("assets/farmer_front.png", WHITE).await;
         let farm = load_image_transparent_color("assets/farm.png", WHITE).await;
-        Self { farmer_front, farm }
+        let pencil = load_sound("assets/pencil.wav")
+            .await
+            .expect("Could not open file");
+        let mut people = Vec::with_capacity(PEOPLE.len());
+        for id in PEOPLE {
+            let contents = load_string(&format!("people/{}.md", id))
+                .await
+                .expect("Could not open file");
+            people.push(Profile::parse(id, &contents));
+        }
+        let mut portraits = HashMap::with_capacity(PORTRAITS.len());
+        for id in PORTRAITS {
+            let path = format!("assets/portraits/{}.png", id);
+            let portrait = load_image_transparent_color(&path, WHITE).await;
+            portrait.set_filter(FilterMode::Nearest);
+            portraits.insert(*id, portrait);
+        }
+        Self {
+            farmer_front,
+            farm,
+            pencil,
+            people,
+            portraits,
+        }
+    }
+
+    /// The picture shown next to the lines spoken by `id`, if there is one
+    pub fn portrait(&self, id: &str) -> Option<Texture2D> {
+        self.portraits.get(id).copied()
     }
 }
 
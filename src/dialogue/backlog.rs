@@ -0,0 +1,99 @@
+use super::{
+    line::{Layouts, Line},
+    Journal, BOTTOM_MARGIN,
+};
+use crate::{
+    pause::PauseMenu,
+    scene::{Ctx, Scene, Transition},
+};
+use macroquad::prelude::*;
+
+const SCROLL_SPEED: f32 = 5.;
+/// Space between two pages in the backlog
+const PAGE_GAP: f32 = 30.;
+/// Where the text starts on the screen
+const TOP: f32 = 50.;
+
+/// Every page in the journal, shown on top of the page that is being read
+pub(super) struct Backlog {
+    pages: Vec<Vec<Line>>,
+    layouts: Vec<Layouts>,
+    /// How far the player scrolled up from the most recent line
+    scroll: f32,
+}
+
+impl Backlog {
+    /// The pages of `journal` followed by the `current` lines
+    pub(super) fn new(journal: Option<&Journal>, current: &[Line]) -> Self {
+        let mut pages: Vec<Vec<Line>> = journal
+            .into_iter()
+            .flat_map(Journal::pages)
+            .map(<[Line]>::to_vec)
+            .collect();
+        pages.push(current.to_vec());
+        let layouts = pages.iter().map(|_| Layouts::default()).collect();
+        Self {
+            pages,
+            layouts,
+            scroll: 0.,
+        }
+    }
+
+    /// How far the backlog can be scrolled up before the oldest line is at the top
+    fn overflow(&mut self, max_width: f32) -> f32 {
+        let mut height = 0.;
+        for (page, layouts) in self.pages.iter().zip(&mut self.layouts) {
+            for (index, line) in page.iter().enumerate() {
+                height += layouts.get(index, line, max_width).height();
+            }
+            height += PAGE_GAP;
+        }
+        (height - (screen_height() - BOTTOM_MARGIN - TOP)).max(0.)
+    }
+}
+
+impl Scene for Backlog {
+    fn update(&mut self, _ctx: &mut Ctx) -> Transition {
+        let overflow = self.overflow(max_width());
+        if is_key_down(KeyCode::Up) {
+            self.scroll = (self.scroll + SCROLL_SPEED).min(overflow);
+        }
+        if is_key_down(KeyCode::Down) {
+            self.scroll = (self.scroll - SCROLL_SPEED).max(0.);
+        }
+        if is_key_pressed(KeyCode::Tab) {
+            Transition::Pop
+        } else if is_key_pressed(KeyCode::Escape) {
+            Transition::push(PauseMenu::default())
+        } else {
+            Transition::Stay
+        }
+    }
+
+    fn draw(&mut self, _ctx: &Ctx) {
+        let x = 50.;
+        let max_width = max_width();
+        let overflow = self.overflow(max_width);
+        let mut y = TOP - overflow + self.scroll;
+        for (page, layouts) in self.pages.iter().zip(&mut self.layouts) {
+            for (index, line) in page.iter().enumerate() {
+                let layout = layouts.get(index, line, max_width);
+                y += line.draw(layout, get_time() as f32, x, y, None);
+            }
+            y += PAGE_GAP;
+        }
+        draw_rectangle(0., screen_height() - 80., screen_width(), 80., BLACK);
+        draw_text("Backlog", 50., screen_height() - 50., 24., GRAY);
+        draw_text(
+            "<UP/DOWN> scroll  <TAB> back",
+            250.,
+            screen_height() - 50.,
+            24.,
+            WHITE,
+        );
+    }
+}
+
+fn max_width() -> f32 {
+    screen_width() - 100.
+}
@@ -0,0 +1,156 @@
+use super::line::{Layouts, Line};
+use crate::{
+    pause::PauseMenu,
+    scene::{Ctx, Scene, Transition},
+};
+use macroquad::prelude::*;
+
+const SCROLL_SPEED: f32 = 5.;
+
+/// A diary page the player has read
+#[derive(Clone)]
+pub struct Entry {
+    lines: Vec<Line>,
+}
+
+/// Every diary page the player has read this game
+#[derive(Default)]
+pub struct Journal {
+    entries: Vec<Entry>,
+}
+
+impl Journal {
+    /// Add a page with the lines that were shown of it
+    pub(super) fn add(&mut self, lines: &[Line]) {
+        self.entries.push(Entry {
+            lines: lines.to_vec(),
+        });
+    }
+
+    /// The lines of every entry, oldest first
+    pub(super) fn pages(&self) -> impl Iterator<Item = &[Line]> {
+        self.entries.iter().map(|entry| entry.lines.as_slice())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The text of every entry, without styling, so it can be saved
+    pub fn to_text(&self) -> Vec<Vec<String>> {
+        self.entries
+            .iter()
+            .map(|entry| entry.lines.iter().map(Line::plain_text).collect())
+            .collect()
+    }
+
+    /// A journal with the entries from `Journal::to_text`
+    pub fn from_text(entries: Vec<Vec<String>>) -> Self {
+        let entries = entries
+            .into_iter()
+            .map(|lines| Entry {
+                lines: lines
+                    .into_iter()
+                    .map(|text| Line::Text { text, color: WHITE })
+                    .collect(),
+            })
+            .collect();
+        Self { entries }
+    }
+}
+
+/// Flipping through the journal of the game that is being played
+pub struct JournalScreen {
+    index: usize,
+    scroll: f32,
+    layouts: Layouts,
+}
+
+impl JournalScreen {
+    /// Start at the most recent page
+    pub fn new(journal: &Journal) -> Self {
+        Self {
+            index: journal.entries.len().saturating_sub(1),
+            scroll: 0.,
+            layouts: Layouts::default(),
+        }
+    }
+
+    /// How far the current page can be scrolled down before its last line is visible
+    fn overflow(&mut self, journal: &Journal) -> f32 {
+        let max_width = screen_width() - 100.;
+        let height: f32 = journal.entries.get(self.index).map_or(0., |entry| {
+            entry
+                .lines
+                .iter()
+                .enumerate()
+                .map(|(index, line)| self.layouts.get(index, line, max_width).height())
+                .sum()
+        });
+        (50. + height - (screen_height() - 90.)).max(0.)
+    }
+
+    fn turn_to(&mut self, index: usize) {
+        self.index = index;
+        self.scroll = 0.;
+        self.layouts = Layouts::default();
+    }
+}
+
+impl Scene for JournalScreen {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        let journal = &ctx.state().journal;
+        if is_key_pressed(KeyCode::Left) && self.index > 0 {
+            self.turn_to(self.index - 1);
+        }
+        if is_key_pressed(KeyCode::Right) && self.index + 1 < journal.entries.len() {
+            self.turn_to(self.index + 1);
+        }
+        let overflow = self.overflow(journal);
+        if is_key_down(KeyCode::Down) {
+            self.scroll = (self.scroll + SCROLL_SPEED).min(overflow);
+        }
+        if is_key_down(KeyCode::Up) {
+            self.scroll = (self.scroll - SCROLL_SPEED).max(0.);
+        }
+        if is_key_pressed(KeyCode::J) {
+            Transition::Pop
+        } else if is_key_pressed(KeyCode::Escape) {
+            Transition::push(PauseMenu::default())
+        } else {
+            Transition::Stay
+        }
+    }
+
+    fn draw(&mut self, ctx: &Ctx) {
+        let journal = &ctx.state().journal;
+        let x = 50.;
+        let max_width = screen_width() - x * 2.;
+        let mut y = 50. - self.scroll;
+        if let Some(entry) = journal.entries.get(self.index) {
+            for (index, line) in entry.lines.iter().enumerate() {
+                let layout = self.layouts.get(index, line, max_width);
+                y += line.draw(layout, get_time() as f32, x, y, None);
+            }
+        }
+
+        draw_rectangle(0., screen_height() - 80., screen_width(), 80., BLACK);
+        draw_text(
+            &format!("Entry {}/{}", self.index + 1, journal.entries.len()),
+            50.,
+            screen_height() - 50.,
+            24.,
+            GRAY,
+        );
+        draw_text(
+            "<LEFT/RIGHT> turn page  <J> back",
+            250.,
+            screen_height() - 50.,
+            24.,
+            WHITE,
+        );
+        if self.overflow(journal) > 0. {
+            draw_text("<UP/DOWN> scroll", 250., screen_height() - 20., 24., WHITE);
+        }
+    }
+}
@@ -1,66 +1,498 @@
 use macroquad::prelude::*;
 
+/// Extra space between the characters of a jiggling line
+const JIGGLE_STEP: f32 = 5.;
+const JIGGLE_DISTANCE: f32 = 2.;
+const JIGGLE_SPEED: f32 = 10.;
+/// Milliseconds a `<pause>` tag holds up the text, if no length is given
+const DEFAULT_PAUSE: u32 = 300;
+const PORTRAIT_SIZE: f32 = 64.;
+/// Space between the portrait and the spoken text
+const PORTRAIT_MARGIN: f32 = 12.;
+/// Distance from the baseline of the first row to the top of the text
+const ASCENT: f32 = 20.;
+
 #[derive(Clone)]
 pub enum Line {
-    BigText { text: String, color: Color },
-    Text { text: String, color: Color },
-    Jiggle { text: String, color: Color },
+    BigText {
+        text: String,
+        color: Color,
+    },
+    Text {
+        text: String,
+        color: Color,
+    },
+    Jiggle {
+        text: String,
+        color: Color,
+    },
+    /// A line mixing styles, see `Span::parse`
+    Rich {
+        spans: Vec<Span>,
+    },
+    /// Something another character said, shown with their name and portrait
+    Speech {
+        speaker: String,
+        portrait: Option<Texture2D>,
+        text: String,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Style {
+    pub color: Color,
+    pub big: bool,
+    pub jiggle: bool,
+}
+
+impl Style {
+    fn plain(color: Color) -> Self {
+        Self {
+            color,
+            big: false,
+            jiggle: false,
+        }
+    }
+
+    fn font_size(&self) -> u16 {
+        if self.big {
+            40
+        } else {
+            24
+        }
+    }
+
+    fn row_height(&self) -> f32 {
+        if self.big {
+            45.
+        } else {
+            30.
+        }
+    }
+
+    fn char_width(&self, char: char) -> f32 {
+        let mut buffer = [0u8; 4];
+        let str = char.encode_utf8(&mut buffer);
+        let width = measure_text(str, None, self.font_size(), 1.0).width;
+        if self.jiggle {
+            width + JIGGLE_STEP
+        } else {
+            width
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Span {
+    Text {
+        text: String,
+        style: Style,
+    },
+    /// Hold up the typewriter for this many milliseconds
+    Pause(u32),
+}
+
+impl Span {
+    /// Parse a line with inline markup, e.g. `"I found <red>blight</red> again<pause>"`.
+    ///
+    /// Supported tags are colors (`red`, `orange`, `yellow`, `green`, `blue`, `gray`, `white`),
+    /// `big`, `jiggle` and `<pause>` or `<pause=milliseconds>`. Anything else between angle brackets is
+    /// kept as text, so lines like `"<Lost 3 potatoes>"` still work. So is a closing tag that
+    /// doesn't close the innermost open tag.
+    pub fn parse(markup: &str, color: Color) -> Vec<Span> {
+        let mut spans = Vec::new();
+        // the open tags with the style they start, innermost last
+        let mut open_tags: Vec<(&str, Style)> = Vec::new();
+        let base = Style::plain(color);
+        let mut text = String::new();
+        let mut rest = markup;
+        while let Some(open) = rest.find('<') {
+            let Some(close) = rest[open..].find('>').map(|close| open + close) else {
+                break;
+            };
+            let style = open_tags.last().map_or(base, |(_, style)| *style);
+            let tag = match &rest[open + 1..close] {
+                name if name.starts_with("pause") => Self::pause(name).map(Tag::Pause),
+                name => match name.strip_prefix('/') {
+                    Some(name) => open_tags
+                        .last()
+                        .filter(|(open_name, _)| *open_name == name)
+                        .map(|_| Tag::Close),
+                    None => Self::tag_style(name, style).map(|style| Tag::Open(name, style)),
+                },
+            };
+
+            text.push_str(&rest[..open]);
+            let Some(tag) = tag else {
+                // not a tag we know, keep it as text
+                text.push_str(&rest[open..=close]);
+                rest = &rest[close + 1..];
+                continue;
+            };
+            if !text.is_empty() {
+                spans.push(Span::Text {
+                    text: std::mem::take(&mut text),
+                    style,
+                });
+            }
+            match tag {
+                Tag::Pause(ms) => spans.push(Span::Pause(ms)),
+                Tag::Open(name, style) => open_tags.push((name, style)),
+                Tag::Close => {
+                    open_tags.pop();
+                }
+            }
+            rest = &rest[close + 1..];
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            spans.push(Span::Text {
+                text,
+                style: open_tags.last().map_or(base, |(_, style)| *style),
+            });
+        }
+        spans
+    }
+
+    /// The milliseconds of a `pause` or `pause=milliseconds` tag
+    fn pause(tag: &str) -> Option<u32> {
+        match tag.strip_prefix("pause")? {
+            "" => Some(DEFAULT_PAUSE),
+            ms => ms.strip_prefix('=').and_then(|ms| ms.parse().ok()),
+        }
+    }
+
+    /// The style inside a `name` tag that is opened within `style`
+    fn tag_style(name: &str, style: Style) -> Option<Style> {
+        let color = match name {
+            "red" => RED,
+            "orange" => ORANGE,
+            "yellow" => YELLOW,
+            "green" => GREEN,
+            "blue" => SKYBLUE,
+            "gray" => GRAY,
+            "white" => WHITE,
+            "big" => return Some(Style { big: true, ..style }),
+            "jiggle" => {
+                return Some(Style {
+                    jiggle: true,
+                    ..style
+                })
+            }
+            _ => return None,
+        };
+        Some(Style { color, ..style })
+    }
+}
+
+/// A tag recognized by `Span::parse`
+enum Tag<'a> {
+    Open(&'a str, Style),
+    /// Closes the innermost open tag
+    Close,
+    Pause(u32),
+}
+
+/// A single step of the typewriter
+#[derive(Copy, Clone)]
+enum Glyph {
+    Char(char, Style),
+    Pause(u32),
+}
+
+impl Glyph {
+    /// How many seconds the typewriter spends on this glyph
+    fn duration(&self, char_time: f32) -> f32 {
+        match self {
+            Self::Char(..) => char_time,
+            Self::Pause(ms) => *ms as f32 / 1000.,
+        }
+    }
+
+    fn width(&self) -> f32 {
+        match self {
+            Self::Char(char, style) => style.char_width(*char),
+            Self::Pause(_) => 0.,
+        }
+    }
 }
 
 impl Line {
-    pub fn str(&self) -> &str {
+    fn glyphs(&self) -> Vec<Glyph> {
+        let (text, style) = match self {
+            Self::BigText { text, color } => (
+                text,
+                Style {
+                    big: true,
+                    ..Style::plain(*color)
+                },
+            ),
+            Self::Text { text, color } => (text, Style::plain(*color)),
+            Self::Speech { text, .. } => (text, Style::plain(WHITE)),
+            Self::Jiggle { text, color } => (
+                text,
+                Style {
+                    jiggle: true,
+                    ..Style::plain(*color)
+                },
+            ),
+            Self::Rich { spans } => {
+                let mut glyphs = Vec::new();
+                for span in spans {
+                    match span {
+                        Span::Text { text, style } => {
+                            glyphs.extend(text.chars().map(|char| Glyph::Char(char, *style)))
+                        }
+                        Span::Pause(ms) => glyphs.push(Glyph::Pause(*ms)),
+                    }
+                }
+                return glyphs;
+            }
+        };
+        text.chars().map(|char| Glyph::Char(char, style)).collect()
+    }
+
+    /// The text of the line without any styling, including who said it
+    pub fn plain_text(&self) -> String {
+        let text = self.glyphs().into_iter().filter_map(|glyph| match glyph {
+            Glyph::Char(char, _) => Some(char),
+            Glyph::Pause(_) => None,
+        });
         match self {
-            Self::BigText { text, .. } => text,
-            Self::Text { text, .. } => text,
-            Self::Jiggle { text, .. } => text,
+            Self::Speech { speaker, .. } => format!("{}: {}", speaker, text.collect::<String>()),
+            _ => text.collect(),
         }
     }
 
-    pub fn draw(&self, timestamp: f32, x: f32, y: f32, len: Option<usize>) -> f32 {
-        let mut str = self.str();
-        if let Some(len) = len {
-            let mut indices = str.char_indices();
-            if let Some((index, _)) = indices.nth(len) {
-                str = &str[..index];
+    /// How many seconds it takes the typewriter to show the entire line, if every character takes
+    /// `char_time` seconds
+    pub fn duration(&self, char_time: f32) -> f32 {
+        self.glyphs()
+            .iter()
+            .map(|glyph| glyph.duration(char_time))
+            .sum()
+    }
+
+    /// How many characters the typewriter has shown after `time` seconds
+    pub fn revealed(&self, time: f32, char_time: f32) -> usize {
+        let mut time = time;
+        let mut chars = 0;
+        for glyph in self.glyphs() {
+            time -= glyph.duration(char_time);
+            if time < 0. {
+                break;
+            }
+            if let Glyph::Char(..) = glyph {
+                chars += 1;
             }
         }
+        chars
+    }
 
+    /// Empty space below the line
+    fn spacing(&self) -> f32 {
         match self {
-            Line::BigText { color, .. } => {
-                draw_text(str, x, y, 40., *color);
-                80.
-            }
-            Line::Text { color, .. } => {
-                draw_text(str, x, y, 24., *color);
-                30.
+            Self::BigText { .. } => 35.,
+            Self::Speech { .. } => 10.,
+            _ => 0.,
+        }
+    }
+
+    /// Horizontal space in front of the text, taken up by the portrait
+    fn indent(&self) -> f32 {
+        match self {
+            Self::Speech { .. } => PORTRAIT_SIZE + PORTRAIT_MARGIN,
+            _ => 0.,
+        }
+    }
+
+    /// Vertical space above the text, taken up by the name of the speaker
+    fn header_height(&self) -> f32 {
+        match self {
+            Self::Speech { .. } => Style::plain(WHITE).row_height(),
+            _ => 0.,
+        }
+    }
+
+    /// Split the line into rows that are at most `max_width` wide, breaking on spaces where
+    /// possible. The spaces stay at the end of the rows, so the rows together are the entire line.
+    /// Every glyph comes with its width.
+    fn rows(&self, max_width: f32) -> Vec<Vec<(Glyph, f32)>> {
+        let glyphs: Vec<_> = self
+            .glyphs()
+            .into_iter()
+            .map(|glyph| (glyph, glyph.width()))
+            .collect();
+        let mut rows = Vec::new();
+        let mut start = 0;
+        let mut last_break = None;
+        let mut width = 0.;
+        let mut width_since_break = 0.;
+        for (index, (glyph, glyph_width)) in glyphs.iter().enumerate() {
+            width += glyph_width;
+            width_since_break += glyph_width;
+            if let Glyph::Char(' ', _) = glyph {
+                last_break = Some(index + 1);
+                width_since_break = 0.;
+                continue;
             }
-            Line::Jiggle { color, .. } => {
-                const SPACING: f32 = 0.;
-                const STEP: f32 = 5.;
-                const DISTANCE: f32 = 2.;
-                const SPEED: f32 = 10.;
-
-                let mut x = x + SPACING;
-                let y = y + SPACING;
-                for (index, char) in str.chars().enumerate() {
-                    let mut buffer = [0u8; 4];
-                    let str = char.encode_utf8(&mut buffer);
-                    let size = measure_text(str, None, 24, 1.0);
-
-                    let angle = (timestamp + (index as f32 / 10.)) * SPEED;
-                    draw_text(
-                        str,
-                        x + angle.cos() * DISTANCE,
-                        y + angle.sin() * DISTANCE,
-                        24.,
-                        *color,
-                    );
-
-                    x += size.width + STEP;
+            if width > max_width {
+                if let Some(last_break) = last_break {
+                    rows.push(glyphs[start..last_break].to_vec());
+                    start = last_break;
+                    width = width_since_break;
+                } else if index > start {
+                    // a single word that doesn't fit on a row
+                    rows.push(glyphs[start..index].to_vec());
+                    start = index;
+                    width = *glyph_width;
+                    width_since_break = *glyph_width;
                 }
+                last_break = None;
+            }
+        }
+        rows.push(glyphs[start..].to_vec());
+        rows
+    }
+
+    fn row_height(row: &[(Glyph, f32)]) -> f32 {
+        row.iter()
+            .map(|(glyph, _)| match glyph {
+                Glyph::Char(_, style) => style.row_height(),
+                Glyph::Pause(_) => 0.,
+            })
+            .fold(Style::plain(WHITE).row_height(), f32::max)
+    }
+
+    /// Wrap the line to `max_width`
+    pub fn layout(&self, max_width: f32) -> Layout {
+        let rows = self.rows(max_width - self.indent());
+        let text_height =
+            self.header_height() + rows.iter().map(|row| Self::row_height(row)).sum::<f32>();
+        let min_height = match self {
+            Self::Speech { .. } => PORTRAIT_SIZE,
+            _ => 0.,
+        };
+        Layout {
+            max_width,
+            rows,
+            height: text_height.max(min_height) + self.spacing(),
+        }
+    }
+
+    /// Draw the line as wrapped by `layout`. If `len` is given, only that many characters are shown.
+    ///
+    /// Returns the height of the entire line, even if not all of it is visible yet.
+    pub fn draw(&self, layout: &Layout, timestamp: f32, x: f32, y: f32, len: Option<usize>) -> f32 {
+        if let Self::Speech {
+            speaker, portrait, ..
+        } = self
+        {
+            draw_portrait(speaker, *portrait, x, y - ASCENT);
+            draw_text(speaker, x + self.indent(), y, 24., YELLOW);
+        }
 
-                30. + SPACING * 2.
+        let mut remaining = len.unwrap_or(usize::MAX);
+        let mut char_index = 0;
+        let mut row_y = y + self.header_height();
+        for row in &layout.rows {
+            let mut x = x + self.indent();
+            for (glyph, width) in row {
+                let Glyph::Char(char, style) = *glyph else {
+                    continue;
+                };
+                if remaining == 0 {
+                    break;
+                }
+                remaining -= 1;
+                let mut buffer = [0u8; 4];
+                let str = char.encode_utf8(&mut buffer);
+                let (dx, dy) = if style.jiggle {
+                    let angle = (timestamp + (char_index as f32 / 10.)) * JIGGLE_SPEED;
+                    (angle.cos() * JIGGLE_DISTANCE, angle.sin() * JIGGLE_DISTANCE)
+                } else {
+                    (0., 0.)
+                };
+                draw_text(
+                    str,
+                    x + dx,
+                    row_y + dy,
+                    style.font_size() as f32,
+                    style.color,
+                );
+                x += width;
+                char_index += 1;
             }
+            row_y += Self::row_height(row);
+        }
+
+        layout.height
+    }
+}
+
+/// A line wrapped to a width. Measuring the text is slow, so this is worked out once and kept
+/// until the width changes.
+pub struct Layout {
+    max_width: f32,
+    rows: Vec<Vec<(Glyph, f32)>>,
+    height: f32,
+}
+
+impl Layout {
+    /// How much vertical space the line takes up
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+}
+
+/// The layouts of a list of lines, worked out the first time a line is needed at a width
+#[derive(Default)]
+pub struct Layouts {
+    layouts: Vec<Option<Layout>>,
+}
+
+impl Layouts {
+    /// The layout of `line`, the line at `index` in the list, wrapped to `max_width`
+    pub fn get(&mut self, index: usize, line: &Line, max_width: f32) -> &Layout {
+        if self.layouts.len() <= index {
+            self.layouts.resize_with(index + 1, || None);
+        }
+        let layout = &mut self.layouts[index];
+        if !layout
+            .as_ref()
+            .is_some_and(|layout| layout.max_width == max_width)
+        {
+            *layout = Some(line.layout(max_width));
+        }
+        layout.as_ref().unwrap()
+    }
+
+    /// Forget the layout of the line at `index`, because it was replaced
+    pub fn invalidate(&mut self, index: usize) {
+        if let Some(layout) = self.layouts.get_mut(index) {
+            *layout = None;
+        }
+    }
+}
+
+/// Draw the portrait of a speaker, or their initial if they don't have one
+fn draw_portrait(speaker: &str, portrait: Option<Texture2D>, x: f32, y: f32) {
+    match portrait {
+        Some(texture) => draw_texture_ex(
+            texture,
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(PORTRAIT_SIZE, PORTRAIT_SIZE)),
+                ..Default::default()
+            },
+        ),
+        None => {
+            draw_rectangle(x, y, PORTRAIT_SIZE, PORTRAIT_SIZE, DARKGRAY);
+            let initial: String = speaker.chars().take(1).collect();
+            draw_text(&initial, x + 20., y + 45., 48., LIGHTGRAY);
         }
     }
+    draw_rectangle_lines(x, y, PORTRAIT_SIZE, PORTRAIT_SIZE, 2., GRAY);
 }
@@ -1,13 +1,33 @@
+mod backlog;
+mod journal;
 mod line;
 mod prompt;
+mod seen;
 
-pub use prompt::Prompt;
+pub use journal::{Journal, JournalScreen};
+pub use prompt::{get_num_pressed, Prompt, PromptLine};
+pub use seen::Seen;
 
-use line::Line;
+use backlog::Backlog;
+use line::{Layouts, Line, Span};
+use macroquad::audio::{play_sound, PlaySoundParams};
 use macroquad::prelude::*;
 use std::time::Instant;
 
-use crate::draw_text_centered;
+use crate::{
+    draw_text_centered,
+    pause::PauseMenu,
+    scene::{Ctx, Scene, Transition},
+};
+
+/// Space at the bottom of the screen that is reserved for the key hints
+const BOTTOM_MARGIN: f32 = 90.;
+/// Seconds between one line being done and the next one starting
+const LINE_PAUSE: f32 = 0.33;
+/// Seconds to wait before turning the page when auto-advance is on
+const AUTO_ADVANCE_DELAY: f32 = 2.5;
+/// Don't play the typing sound more often than this, in seconds
+const TYPING_SOUND_INTERVAL: f32 = 0.06;
 
 pub trait DialogueBuilder {
     fn lines_mut(&mut self) -> &mut Vec<Line>;
@@ -33,6 +53,27 @@ pub trait DialogueBuilder {
         });
         self
     }
+    /// A line with inline markup for colors, jiggling, big text and pauses, see `Span::parse`
+    fn rich_text(&mut self, markup: impl AsRef<str>) -> &mut Self {
+        self.lines_mut().push(Line::Rich {
+            spans: Span::parse(markup.as_ref(), WHITE),
+        });
+        self
+    }
+    /// A line spoken by `speaker`, shown next to their portrait
+    fn say(
+        &mut self,
+        speaker: impl Into<String>,
+        portrait: Option<Texture2D>,
+        text: impl Into<String>,
+    ) -> &mut Self {
+        self.lines_mut().push(Line::Speech {
+            speaker: speaker.into(),
+            portrait,
+            text: text.into(),
+        });
+        self
+    }
     fn jiggle_text(&mut self, text: impl Into<String>) -> &mut Self {
         self.jiggle_color_text(text, WHITE);
         self
@@ -51,119 +92,193 @@ pub trait DialogueBuilder {
         self
     }
 }
+/// A diary page. The text appears like it's being written, and the page closes once the player
+/// is done reading.
 pub struct Dialogue {
     lines: Vec<Line>,
+    layouts: Layouts,
+    /// The line that is being written, the ones before it are fully visible
+    line_idx: usize,
+    /// Seconds the typewriter has spent on the current line
+    line_time: f32,
+    /// Seconds since all the text became visible
+    idle_time: f32,
+    last_sound: f32,
+    /// The sheet the player flipped back to, or `None` to follow the text as it appears
+    viewed_sheet: Option<usize>,
+    started: Instant,
+    /// Whether `<Enter>` closes the page once all the text is visible
+    enable_enter_continue: bool,
+}
+
+/// What the page does after a frame
+enum Step {
+    Continue,
+    /// Another screen opens on top of the page
+    Open(Transition),
+    /// The player is done with the page
+    Done,
 }
 
 impl Dialogue {
     pub fn new(constructor: impl FnOnce(&mut Dialogue)) -> Self {
-        let mut d = Self { lines: Vec::new() };
+        let mut d = Self {
+            lines: Vec::new(),
+            layouts: Layouts::default(),
+            line_idx: 1,
+            line_time: 0.,
+            idle_time: 0.,
+            last_sound: 0.,
+            viewed_sheet: None,
+            started: Instant::now(),
+            enable_enter_continue: true,
+        };
         constructor(&mut d);
         d
     }
-    pub async fn show(constructor: impl FnOnce(&mut Dialogue)) {
-        Self::new(constructor).render().await;
+
+    fn all_text_visible(&self) -> bool {
+        self.lines.len() <= self.line_idx
+    }
+
+    /// The lines that were at least partly shown so far
+    fn shown(&self) -> &[Line] {
+        &self.lines[..(self.line_idx + 1).min(self.lines.len())]
     }
 
-    pub async fn render_with_opts<FN>(mut self, opts: &mut DialogueOpts<FN>)
-    where
-        FN: FnMut(FrameCtx) -> Event,
-    {
-        let mut enable_enter_continue = opts.enable_enter_continue;
-        let mut line_idx = 1;
-        let mut char_idx = 0;
-        let mut pause_time = 0;
+    /// Write the lines that were shown so far into `journal`, also when the page is left early
+    pub fn record(&self, journal: &mut Journal) {
+        journal.add(&self.lines[..self.line_idx.min(self.lines.len())]);
+    }
 
-        let event_cb = opts
-            .events
-            .as_mut()
-            .expect("DialogueOpts doesn't have an event callback");
-        let start = Instant::now();
+    /// The area the text is drawn in
+    fn text_area() -> (f32, f32, f32, f32) {
+        let x = 50.;
+        let y = 50.;
+        (
+            x,
+            y,
+            screen_width() - x * 2.,
+            screen_height() - y - BOTTOM_MARGIN,
+        )
+    }
 
-        loop {
-            clear_background(BLACK);
+    /// Run the typewriter for a frame, with `event` deciding how the page reacts to the player
+    fn step(&mut self, ctx: &mut Ctx, event: impl FnOnce(&mut Dialogue) -> Event) -> Step {
+        let settings = ctx.settings;
+        let char_time = 1. / settings.text_speed.chars_per_second();
 
-            let x = 50.;
-            let mut y = 50.;
+        let (_, _, max_width, height) = Self::text_area();
+        let count = self.shown().len();
+        let last_sheet = self.sheets(count, max_width, height).len() - 1;
+        if is_key_pressed(KeyCode::Left) {
+            self.viewed_sheet = Some(self.viewed_sheet.unwrap_or(last_sheet).saturating_sub(1));
+        }
+        if is_key_pressed(KeyCode::Right) {
+            self.viewed_sheet = self
+                .viewed_sheet
+                .map(|sheet| sheet + 1)
+                .filter(|&sheet| sheet < last_sheet);
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return Step::Open(Transition::push(PauseMenu::default()));
+        }
+        if is_key_pressed(KeyCode::Tab) {
+            let journal = ctx.game.as_ref().map(|state| &state.journal);
+            return Step::Open(Transition::push(Backlog::new(journal, self.shown())));
+        }
 
-            let timestamp = start.elapsed().as_secs_f32();
-            for line in &self.lines[..line_idx] {
-                let height = line.draw(timestamp, x, y, None);
-                y += height;
-            }
+        if self.all_text_visible() && self.enable_enter_continue && settings.auto_advance {
+            self.idle_time += get_frame_time();
+        } else {
+            self.idle_time = 0.;
+        }
 
-            if let Some(line) = self.lines.get(line_idx) {
-                line.draw(timestamp, x, y, Some(char_idx));
-                draw_text_centered(
-                    "<SPACE> skip",
-                    screen_width() / 2.0,
-                    screen_height() - 50.,
-                    24.,
-                    WHITE,
-                );
-            } else {
-                if enable_enter_continue {
-                    draw_text_centered(
-                        "<ENTER> continue",
-                        screen_width() / 2.0,
-                        screen_height() - 50.,
-                        24.,
-                        WHITE,
-                    );
-                }
-                if opts.intro {
-                    draw_text(
-                        "<ESC> skip intro",
-                        screen_width() - 200.,
-                        screen_height() - 50.,
-                        24.,
-                        WHITE,
-                    );
+        let event = event(self);
+        let event = if self.idle_time > AUTO_ADVANCE_DELAY {
+            Event::Done
+        } else {
+            event
+        };
+        match event {
+            Event::Done => {
+                let shown = &self.lines[..self.line_idx.min(self.lines.len())];
+                if let Some(state) = &mut ctx.game {
+                    self.record(&mut state.journal);
                 }
+                ctx.seen.mark(shown);
+                return Step::Done;
             }
-
-            let event = event_cb(FrameCtx {
-                all_text_visible: self.lines.len() <= line_idx,
-                dialogue: &mut self,
-                enable_enter_continue: &mut enable_enter_continue,
-            });
-            match event {
-                Event::Done => return,
-                Event::ShowText => {
-                    line_idx = self.lines.len();
+            Event::ShowText => {
+                self.line_idx = self.lines.len();
+            }
+            Event::Idle => {}
+            Event::NextChar => {
+                if settings.skip_seen {
+                    while self
+                        .lines
+                        .get(self.line_idx)
+                        .is_some_and(|line| ctx.seen.contains(line))
+                    {
+                        self.line_time = 0.;
+                        self.line_idx += 1;
+                    }
                 }
-                Event::Idle => {}
-                Event::NextChar => {
-                    if let Some(line) = self.lines.get(line_idx) {
-                        if pause_time == 0 {
-                            char_idx += 1;
-                            if char_idx >= line.str().chars().count() {
-                                char_idx = 0;
-                                line_idx += 1;
-                                pause_time = 20;
-                            }
-                        } else {
-                            pause_time -= 1;
-                        }
+                if let Some(line) = self.lines.get(self.line_idx) {
+                    let timestamp = self.started.elapsed().as_secs_f32();
+                    let revealed = line.revealed(self.line_time, char_time);
+                    self.line_time += get_frame_time();
+                    if settings.typing_sound
+                        && line.revealed(self.line_time, char_time) > revealed
+                        && timestamp - self.last_sound > TYPING_SOUND_INTERVAL
+                    {
+                        play_sound(
+                            ctx.assets.pencil,
+                            PlaySoundParams {
+                                looped: false,
+                                volume: 0.3,
+                            },
+                        );
+                        self.last_sound = timestamp;
+                    }
+                    if self.line_time >= line.duration(char_time) + LINE_PAUSE {
+                        self.line_time = 0.;
+                        self.line_idx += 1;
                     }
                 }
             }
+        }
+        Step::Continue
+    }
 
-            next_frame().await;
+    /// Split the first `count` lines into sheets that each fit in `height`.
+    ///
+    /// Returns the index of the first line of every sheet.
+    fn sheets(&mut self, count: usize, max_width: f32, height: f32) -> Vec<usize> {
+        let mut sheets = vec![0];
+        let mut used = 0.;
+        for (index, line) in self.lines[..count].iter().enumerate() {
+            let line_height = self.layouts.get(index, line, max_width).height();
+            if used > 0. && used + line_height > height {
+                sheets.push(index);
+                used = 0.;
+            }
+            used += line_height;
         }
+        sheets
     }
 
-    pub async fn render_with_events(self, events: impl FnMut(FrameCtx) -> Event) {
-        self.render_with_opts(&mut DialogueOpts {
-            events: Some(events),
-            ..Default::default()
-        })
-        .await;
+    /// Replace the line at `index`, e.g. to highlight the option the player chose
+    fn set_line(&mut self, index: usize, line: Line) {
+        self.lines[index] = line;
+        self.layouts.invalidate(index);
     }
+}
 
-    pub async fn render(self) {
-        self.render_with_events(|ctx| {
-            if ctx.all_text_visible {
+impl Scene for Dialogue {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        let step = self.step(ctx, |dialogue| {
+            if dialogue.all_text_visible() {
                 if is_key_pressed(KeyCode::Enter) {
                     Event::Done
                 } else {
@@ -174,47 +289,85 @@ impl Dialogue {
             } else {
                 Event::NextChar
             }
-        })
-        .await;
+        });
+        match step {
+            Step::Continue => Transition::Stay,
+            Step::Open(transition) => transition,
+            Step::Done => Transition::Pop,
+        }
     }
-}
 
-impl DialogueBuilder for Dialogue {
-    fn lines_mut(&mut self) -> &mut Vec<Line> {
-        &mut self.lines
-    }
-}
+    fn draw(&mut self, ctx: &Ctx) {
+        let char_time = 1. / ctx.settings.text_speed.chars_per_second();
 
-#[derive(Clone)]
-pub struct DialogueOpts<FN> {
-    pub enable_enter_continue: bool,
-    pub intro: bool,
-    pub events: Option<FN>,
-    pub post_render: Option<fn()>,
-}
+        let (x, mut y, max_width, height) = Self::text_area();
+        let shown = self.shown().len();
+        let sheets = self.sheets(shown, max_width, height);
+        let last_sheet = sheets.len() - 1;
+        let sheet = self.viewed_sheet.unwrap_or(last_sheet);
+        let first_line = sheets[sheet];
+        let end_line = sheets.get(sheet + 1).copied().unwrap_or(shown);
 
-impl<FN> Default for DialogueOpts<FN>
-where
-    FN: FnMut(FrameCtx) -> Event,
-{
-    fn default() -> Self {
-        Self {
-            enable_enter_continue: true,
-            intro: false,
-            events: None,
-            post_render: None,
+        let timestamp = self.started.elapsed().as_secs_f32();
+        for index in first_line..end_line {
+            let line = &self.lines[index];
+            let len = if index == self.line_idx {
+                Some(line.revealed(self.line_time, char_time))
+            } else {
+                None
+            };
+            let layout = self.layouts.get(index, line, max_width);
+            y += line.draw(layout, timestamp, x, y, len);
+        }
+        draw_text_centered(
+            "<TAB> backlog",
+            screen_width() / 2.,
+            screen_height() - 20.,
+            24.,
+            GRAY,
+        );
+        if sheets.len() > 1 {
+            draw_text(
+                &format!("<LEFT/RIGHT> sheet {}/{}", sheet + 1, sheets.len()),
+                50.,
+                screen_height() - 20.,
+                24.,
+                GRAY,
+            );
+        }
+
+        if !self.all_text_visible() {
+            draw_text_centered(
+                "<SPACE> skip",
+                screen_width() / 2.0,
+                screen_height() - 50.,
+                24.,
+                WHITE,
+            );
+        } else if self.enable_enter_continue {
+            draw_text_centered(
+                if ctx.settings.auto_advance {
+                    "<ENTER> continue (auto)"
+                } else {
+                    "<ENTER> continue"
+                },
+                screen_width() / 2.0,
+                screen_height() - 50.,
+                24.,
+                WHITE,
+            );
         }
     }
 }
 
-pub struct FrameCtx<'a> {
-    pub all_text_visible: bool,
-    pub dialogue: &'a mut Dialogue,
-    pub enable_enter_continue: &'a mut bool,
+impl DialogueBuilder for Dialogue {
+    fn lines_mut(&mut self) -> &mut Vec<Line> {
+        &mut self.lines
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-pub enum Event {
+enum Event {
     Done,
     ShowText,
     Idle,
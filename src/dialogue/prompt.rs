@@ -1,116 +1,264 @@
-use std::num::NonZeroUsize;
+use macroquad::prelude::{
+    draw_rectangle, draw_text, get_frame_time, is_key_pressed, screen_height, screen_width,
+    KeyCode, GRAY, RED, WHITE, YELLOW,
+};
 
-use macroquad::prelude::{is_key_pressed, KeyCode, YELLOW};
+use super::{line::Line, Dialogue, DialogueBuilder, Event, Step};
+use crate::scene::{Ctx, Scene, Transition};
 
-use super::{line::Line, Dialogue, DialogueBuilder, DialogueOpts, FrameCtx};
-
-#[derive(Default)]
-pub struct Prompt {
+/// A diary page that ends with a choice. Every option carries a value of type `T`, which is what
+/// the prompt returns once the player picks that option.
+pub struct Prompt<T> {
     lines: Vec<Line>,
-    options: Vec<PromptLine>,
-    skippable: bool,
+    options: Vec<PromptLine<T>>,
+    /// The value returned when the player continues without choosing anything
+    skip: Option<T>,
+    timeout: Option<Timeout>,
+}
+
+/// Choose an option for the player if they take too long
+#[derive(Copy, Clone)]
+struct Timeout {
+    seconds: f32,
+    /// The index of the option that is chosen when time runs out
+    default: usize,
 }
 
-impl Prompt {
+impl<T> Prompt<T> {
     pub fn new<FN>(builder: FN) -> Self
     where
-        FN: FnOnce(&mut Prompt),
+        FN: FnOnce(&mut Prompt<T>),
     {
-        let mut prompt = Self::default();
+        let mut prompt = Self {
+            lines: Vec::new(),
+            options: Vec::new(),
+            skip: None,
+            timeout: None,
+        };
         builder(&mut prompt);
+        if let Some(timeout) = prompt.timeout {
+            // otherwise the countdown would run out without anything being chosen
+            assert!(
+                prompt
+                    .options
+                    .iter()
+                    .any(|o| o.index == timeout.default && o.disabled.is_none()),
+                "The default option {} of a timed prompt doesn't exist or is disabled",
+                timeout.default
+            );
+        }
         prompt
     }
 
-    pub fn add_numbered_option(
-        &mut self,
-        index: usize,
-        text: impl Into<String>,
-    ) -> &mut PromptLine {
+    fn push_option(&mut self, index: usize, text: String, choice: Choice<T>) -> &mut PromptLine<T> {
         self.options.push(PromptLine {
             index,
-            text: text.into(),
+            text,
             lines: Vec::new(),
+            disabled: None,
+            choice,
         });
         self.options.last_mut().unwrap()
     }
 
-    pub fn add_option(&mut self, text: impl Into<String>) -> &mut PromptLine {
-        self.add_numbered_option(
-            self.options
-                .iter()
-                .map(|o| o.index)
-                .max()
-                .unwrap_or_default()
-                + 1,
-            text,
-        )
+    fn next_index(&self) -> usize {
+        self.options
+            .iter()
+            .map(|o| o.index)
+            .max()
+            .unwrap_or_default()
+            + 1
     }
 
-    pub fn skippable(&mut self) {
-        self.skippable = true;
+    pub fn add_numbered_option(
+        &mut self,
+        index: usize,
+        text: impl Into<String>,
+        value: T,
+    ) -> &mut PromptLine<T> {
+        self.push_option(index, text.into(), Choice::Value(value))
     }
 
-    pub async fn show<FN>(builder: FN) -> usize
-    where
-        FN: FnOnce(&mut Prompt),
-    {
-        Self::new(builder).render().await
+    pub fn add_option(&mut self, text: impl Into<String>, value: T) -> &mut PromptLine<T> {
+        self.add_numbered_option(self.next_index(), text, value)
     }
-    pub async fn render(mut self) -> usize {
-        let mut result: Option<NonZeroUsize> = None;
-        Dialogue::new(|d| {
-            for line in self.lines {
-                d.lines.push(line);
+
+    /// An option that leads to another choice. The lines of the option are shown first, followed by
+    /// the lines and options of the nested prompt.
+    pub fn add_branch(
+        &mut self,
+        text: impl Into<String>,
+        builder: impl FnOnce(&mut Prompt<T>),
+    ) -> &mut PromptLine<T> {
+        let index = self.next_index();
+        self.push_option(index, text.into(), Choice::Prompt(Prompt::new(builder)))
+    }
+
+    /// Allow the player to continue without choosing, in which case `value` is returned
+    pub fn skippable(&mut self, value: T) {
+        self.skip = Some(value);
+    }
+
+    /// Give the player `seconds` to choose once all the options are visible. When time runs out,
+    /// the option numbered `default` is chosen, which has to be enabled once the prompt is built.
+    pub fn timed(&mut self, seconds: f32, default: usize) {
+        self.timeout = Some(Timeout { seconds, default });
+    }
+
+    /// Add the lines and the options of this prompt to the dialogue.
+    ///
+    /// Returns the index of the first option line.
+    fn push_lines(&mut self, lines: &mut Vec<Line>) -> usize {
+        lines.append(&mut self.lines);
+        let start = lines.len();
+        lines.extend(self.options.iter().map(PromptLine::line));
+        start
+    }
+}
+
+impl<T: 'static> Prompt<T> {
+    /// Show the prompt. Once the player chose an option and closed the page, `then` is called with
+    /// the value of the option, and the transition it returns is made.
+    pub fn then(mut self, then: impl FnOnce(T, &mut Ctx) -> Transition + 'static) -> PromptPage<T> {
+        let mut options_start = 0;
+        let mut dialogue = Dialogue::new(|d| options_start = self.push_lines(&mut d.lines));
+        dialogue.enable_enter_continue = self.skip.is_some();
+        PromptPage {
+            dialogue,
+            options: self.options,
+            options_start,
+            skip: self.skip,
+            time_left: self.timeout.map(|t| t.seconds).unwrap_or_default(),
+            timeout: self.timeout,
+            result: None,
+            then: Some(Box::new(then)),
+        }
+    }
+}
+
+/// A prompt that is being shown
+pub struct PromptPage<T> {
+    dialogue: Dialogue,
+    /// The options that can still be chosen
+    options: Vec<PromptLine<T>>,
+    /// The index of the line of the first option
+    options_start: usize,
+    skip: Option<T>,
+    timeout: Option<Timeout>,
+    time_left: f32,
+    result: Option<T>,
+    then: Option<Then<T>>,
+}
+
+/// What happens with the value of the chosen option
+type Then<T> = Box<dyn FnOnce(T, &mut Ctx) -> Transition>;
+
+impl<T> PromptPage<T> {
+    /// Pick the option numbered `num_pressed`, if it can be chosen
+    fn choose(&mut self, num_pressed: usize) {
+        let Some(idx) = self
+            .options
+            .iter()
+            .position(|o| o.index == num_pressed && o.disabled.is_none())
+        else {
+            return;
+        };
+        self.dialogue.set_line(
+            self.options_start + idx,
+            Line::Text {
+                text: self.options[idx].label(),
+                color: YELLOW,
+            },
+        );
+
+        let option = self.options.remove(idx);
+        self.dialogue.lines.extend(option.lines);
+        match option.choice {
+            Choice::Value(value) => {
+                self.result = Some(value);
+                self.dialogue.enable_enter_continue = true;
             }
-            for option in &self.options {
-                d.text(format!("<{}> {}", option.index, option.text));
+            Choice::Prompt(mut prompt) => {
+                self.options_start = prompt.push_lines(&mut self.dialogue.lines);
+                self.options = std::mem::take(&mut prompt.options);
+                self.dialogue.enable_enter_continue = prompt.skip.is_some();
+                self.skip = prompt.skip;
+                self.timeout = prompt.timeout;
+                self.time_left = self.timeout.map(|t| t.seconds).unwrap_or_default();
             }
-        })
-        .render_with_opts(&mut DialogueOpts {
-            enable_enter_continue: self.skippable,
-            events: Some(|ctx: FrameCtx| {
-                if result.is_none() && ctx.all_text_visible {
-                    if let Some(num_pressed) = get_num_pressed() {
-                        if let Some(idx) = self.options.iter().position(|o| o.index == num_pressed)
-                        {
-                            let offset = ctx.dialogue.lines.len() - self.options.len() + idx;
-                            let text = match ctx.dialogue.lines[offset].clone() {
-                                Line::Text { text, .. } => text,
-                                _ => unimplemented!(),
-                            };
-                            ctx.dialogue.lines[offset] = Line::Text {
-                                text,
-                                color: YELLOW,
-                            };
-
-                            let option = self.options.remove(idx);
-                            for line in option.lines.iter().cloned() {
-                                ctx.dialogue.lines.push(line);
-                            }
-                            result = NonZeroUsize::new(option.index);
-                            *ctx.enable_enter_continue = true;
-                        }
-                    }
-                }
-                if !ctx.all_text_visible && is_key_pressed(KeyCode::Space) {
-                    crate::dialogue::Event::ShowText
-                } else if ctx.all_text_visible
-                    && (*ctx.enable_enter_continue || result.is_some())
-                    && is_key_pressed(KeyCode::Enter)
-                {
-                    crate::dialogue::Event::Done
-                } else {
-                    crate::dialogue::Event::NextChar
+        }
+    }
+}
+
+impl<T: 'static> Scene for PromptPage<T> {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        if self.result.is_none() && self.dialogue.all_text_visible() {
+            let mut chosen = get_num_pressed();
+            if self.timeout.is_some() {
+                self.time_left -= get_frame_time();
+                if self.time_left <= 0. {
+                    chosen = self.timeout.map(|t| t.default);
                 }
-            }),
-            ..Default::default()
-        })
-        .await;
-        result.map(|r| r.get()).unwrap_or_default()
+            }
+            if let Some(num_pressed) = chosen {
+                self.choose(num_pressed);
+            }
+        }
+
+        let has_result = self.result.is_some();
+        let step = self.dialogue.step(ctx, |dialogue| {
+            if !dialogue.all_text_visible() && is_key_pressed(KeyCode::Space) {
+                Event::ShowText
+            } else if dialogue.all_text_visible()
+                && (dialogue.enable_enter_continue || has_result)
+                && is_key_pressed(KeyCode::Enter)
+            {
+                Event::Done
+            } else {
+                Event::NextChar
+            }
+        });
+        match step {
+            Step::Continue => Transition::Stay,
+            Step::Open(transition) => transition,
+            Step::Done => {
+                let result = self
+                    .result
+                    .take()
+                    .or(self.skip.take())
+                    .expect("Prompt was closed without choosing an option");
+                let then = self.then.take().expect("Prompt was closed twice");
+                then(result, ctx)
+            }
+        }
     }
+
+    fn draw(&mut self, ctx: &Ctx) {
+        self.dialogue.draw(ctx);
+        if let (None, Some(timeout)) = (&self.result, self.timeout) {
+            if self.dialogue.all_text_visible() {
+                draw_countdown(self.time_left / timeout.seconds);
+            }
+        }
+    }
+}
+
+/// Draw a bar that shrinks as the time to choose runs out, `left` going from 1 to 0
+fn draw_countdown(left: f32) {
+    let width = screen_width() - 100.;
+    let y = screen_height() - 80.;
+    draw_rectangle(50., y, width, 6., GRAY);
+    draw_rectangle(50., y, width * left.max(0.), 6., RED);
+    draw_text(
+        "Choose quickly!",
+        screen_width() - 200.,
+        screen_height() - 20.,
+        24.,
+        RED,
+    );
 }
 
-fn get_num_pressed() -> Option<usize> {
+pub fn get_num_pressed() -> Option<usize> {
     let mapping = [
         KeyCode::Key0,
         KeyCode::Key1,
@@ -131,19 +279,57 @@ fn get_num_pressed() -> Option<usize> {
     None
 }
 
-impl DialogueBuilder for Prompt {
+impl<T> DialogueBuilder for Prompt<T> {
     fn lines_mut(&mut self) -> &mut Vec<Line> {
         &mut self.lines
     }
 }
 
-pub struct PromptLine {
+/// What happens when an option is chosen
+enum Choice<T> {
+    Value(T),
+    Prompt(Prompt<T>),
+}
+
+pub struct PromptLine<T> {
     pub index: usize,
     pub text: String,
     pub lines: Vec<Line>,
+    /// Why the option can't be chosen right now, shown next to it
+    pub disabled: Option<String>,
+    choice: Choice<T>,
+}
+
+impl<T> PromptLine<T> {
+    /// Only allow this option if `condition` holds, otherwise show it grayed out with `reason`.
+    ///
+    /// If several conditions fail, the first reason is shown.
+    pub fn enabled_if(&mut self, condition: bool, reason: impl Into<String>) -> &mut Self {
+        if !condition && self.disabled.is_none() {
+            self.disabled = Some(reason.into());
+        }
+        self
+    }
+
+    fn label(&self) -> String {
+        format!("<{}> {}", self.index, self.text)
+    }
+
+    fn line(&self) -> Line {
+        match &self.disabled {
+            Some(reason) => Line::Text {
+                text: format!("{} ({})", self.label(), reason),
+                color: GRAY,
+            },
+            None => Line::Text {
+                text: self.label(),
+                color: WHITE,
+            },
+        }
+    }
 }
 
-impl DialogueBuilder for PromptLine {
+impl<T> DialogueBuilder for PromptLine<T> {
     fn lines_mut(&mut self) -> &mut Vec<Line> {
         &mut self.lines
     }
@@ -0,0 +1,58 @@
+use super::line::Line;
+use std::{collections::HashSet, fs, io::Write};
+
+/// Where the lines the player has read are remembered between runs
+const SEEN_PATH: &str = "seen.txt";
+
+/// Every line the player has read, in this run or a previous one
+pub struct Seen {
+    hashes: HashSet<u64>,
+}
+
+impl Seen {
+    pub fn load() -> Self {
+        // the file doesn't exist until the first page has been read
+        let contents = fs::read_to_string(SEEN_PATH).unwrap_or_default();
+        let hashes = contents
+            .lines()
+            .filter_map(|line| u64::from_str_radix(line.trim(), 16).ok())
+            .collect();
+        Self { hashes }
+    }
+
+    pub(super) fn contains(&self, line: &Line) -> bool {
+        self.hashes.contains(&hash(line))
+    }
+
+    /// Remember that the player has read these lines
+    pub(super) fn mark(&mut self, lines: &[Line]) {
+        let new: Vec<u64> = lines
+            .iter()
+            .map(hash)
+            .filter(|&hash| self.hashes.insert(hash))
+            .collect();
+        if new.is_empty() {
+            return;
+        }
+        let result = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(SEEN_PATH)
+            .and_then(|mut file| {
+                new.iter()
+                    .try_for_each(|hash| writeln!(file, "{:016x}", hash))
+            });
+        if let Err(e) = result {
+            eprintln!("Could not save {}: {}", SEEN_PATH, e);
+        }
+    }
+}
+
+/// FNV-1a, which unlike the std hasher is guaranteed to stay the same between Rust versions
+fn hash(line: &Line) -> u64 {
+    line.plain_text()
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
@@ -0,0 +1,95 @@
+use crate::draw_text_centered;
+use ::rand::{thread_rng, Rng};
+use macroquad::prelude::*;
+
+/// How long floating text stays visible, in seconds
+const TEXT_DURATION: f64 = 1.2;
+/// How far floating text rises before it disappears
+const TEXT_RISE: f32 = 40.;
+const PARTICLE_DURATION: f64 = 0.6;
+const PARTICLE_GRAVITY: f32 = 300.;
+
+/// Short-lived visual feedback like floating text and particles.
+///
+/// Effects are positioned in the coordinates of the screen that spawned them, the screen passes its
+/// camera offset when drawing.
+#[derive(Default)]
+pub struct Effects {
+    effects: Vec<Effect>,
+}
+
+struct Effect {
+    kind: EffectKind,
+    x: f32,
+    y: f32,
+    started: f64,
+}
+
+enum EffectKind {
+    Text { text: String, color: Color },
+    Particle { vx: f32, vy: f32, color: Color },
+}
+
+impl EffectKind {
+    fn duration(&self) -> f64 {
+        match self {
+            Self::Text { .. } => TEXT_DURATION,
+            Self::Particle { .. } => PARTICLE_DURATION,
+        }
+    }
+}
+
+impl Effects {
+    /// Text that floats up and fades out, e.g. "+3 potatoes"
+    pub fn text(&mut self, text: impl Into<String>, x: f32, y: f32, color: Color) {
+        self.effects.push(Effect {
+            kind: EffectKind::Text {
+                text: text.into(),
+                color,
+            },
+            x,
+            y,
+            started: get_time(),
+        });
+    }
+
+    /// A handful of particles flying up and falling back down, e.g. dirt when digging
+    pub fn burst(&mut self, x: f32, y: f32, color: Color, count: usize) {
+        let mut rng = thread_rng();
+        for _ in 0..count {
+            self.effects.push(Effect {
+                kind: EffectKind::Particle {
+                    vx: rng.gen_range(-60.0..60.0),
+                    vy: rng.gen_range(-150.0..-60.0),
+                    color,
+                },
+                x,
+                y,
+                started: get_time(),
+            });
+        }
+    }
+
+    /// Draw all running effects, and forget the ones that are done
+    pub fn draw(&mut self, (cam_x, cam_y): (f32, f32)) {
+        let now = get_time();
+        self.effects
+            .retain(|effect| now - effect.started < effect.kind.duration());
+        for effect in &self.effects {
+            let t = (now - effect.started) as f32;
+            let progress = t / effect.kind.duration() as f32;
+            let (x, y) = (effect.x - cam_x, effect.y - cam_y);
+            match &effect.kind {
+                EffectKind::Text { text, color } => {
+                    let color = Color::new(color.r, color.g, color.b, 1. - progress);
+                    draw_text_centered(text, x, y - TEXT_RISE * progress, 24., color);
+                }
+                EffectKind::Particle { vx, vy, color } => {
+                    let px = x + vx * t;
+                    let py = y + vy * t + PARTICLE_GRAVITY * t * t / 2.;
+                    draw_rectangle(px - 2., py - 2., 4., 4., *color);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::{Inventory, Item};
+use macroquad::prelude::*;
+
+/// Where the heap sits on the farm screen, left of the field
+const COMPOST_POS: (f32, f32) = (60., 420.);
+const COMPOST_SIZE: (f32, f32) = (70., 40.);
+/// How close the farmer needs to be to use the heap
+const COMPOST_RANGE: f32 = 60.;
+/// Units of waste that rot down every day
+const ROT_PER_DAY: u32 = 2;
+/// Units of rotten waste needed for a bag of fertilizer
+const WASTE_PER_FERTILIZER: u32 = 4;
+
+/// Items that can go on the compost heap
+const COMPOSTABLE: &[Item] = &[Item::CropWaste, Item::SpoiledFood];
+
+#[derive(Default)]
+pub struct Compost {
+    /// Waste that still has to rot
+    pub waste: u32,
+    /// Waste that has rotted, but not enough to make a bag of fertilizer yet
+    pub rotted: u32,
+    /// Bags of fertilizer ready to be taken
+    pub ready: usize,
+}
+
+impl Compost {
+    pub fn end_of_day(&mut self) {
+        let rot = self.waste.min(ROT_PER_DAY);
+        self.waste -= rot;
+        self.rotted += rot;
+        self.ready += (self.rotted / WASTE_PER_FERTILIZER) as usize;
+        self.rotted %= WASTE_PER_FERTILIZER;
+    }
+
+    pub fn is_near(&self, (px, py): (f32, f32)) -> bool {
+        let cx = COMPOST_POS.0 + COMPOST_SIZE.0 / 2.;
+        let cy = COMPOST_POS.1 + COMPOST_SIZE.1 / 2.;
+        (px - cx).hypot(py - cy) < COMPOST_RANGE
+    }
+
+    /// What pressing enter at the heap would do, if anything
+    pub fn action_name(&self, inventory: &Inventory) -> Option<&'static str> {
+        if self.ready > 0 {
+            Some("take fertilizer")
+        } else if COMPOSTABLE.iter().any(|item| inventory.count(*item) > 0) {
+            Some("add to compost")
+        } else {
+            None
+        }
+    }
+
+    /// Take out the finished fertilizer, or throw all compostable items on the heap
+    pub fn execute(&mut self, inventory: &mut Inventory) {
+        if self.ready > 0 {
+            inventory.add(Item::Fertilizer, self.ready);
+            self.ready = 0;
+            return;
+        }
+        for item in COMPOSTABLE {
+            let count = inventory.count(*item);
+            inventory.remove_all(*item);
+            self.waste += count as u32;
+        }
+    }
+
+    /// Draw the heap, with `camera` being the scroll position of the farm screen
+    pub fn draw(&self, camera: (f32, f32)) {
+        let (x, y) = (COMPOST_POS.0 - camera.0, COMPOST_POS.1 - camera.1);
+        let (w, h) = COMPOST_SIZE;
+        draw_rectangle(x, y + h / 2., w, h / 2., DARKBROWN);
+        draw_circle(x + w / 2., y + h / 2., h / 2., DARKBROWN);
+        if self.ready > 0 {
+            draw_circle(x + w / 2., y + h / 2., 6., GREEN);
+        }
+        draw_text("Compost", x, y + h + 16., 20., WHITE);
+    }
+}
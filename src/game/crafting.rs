@@ -0,0 +1,173 @@
+use super::{Inventory, Item};
+use crate::{
+    dialogue::get_num_pressed,
+    effects::Effects,
+    pause::PauseMenu,
+    scene::{Ctx, Scene, Transition},
+};
+use macroquad::prelude::*;
+
+const RECIPES_PER_COLUMN: usize = 5;
+
+static RECIPES: &[Recipe] = &[
+    Recipe {
+        output: Item::Hoe,
+        description: "Harvest all ripe plants around you at once",
+        ingredients: &[(Item::Wood, 2), (Item::Scrap, 1)],
+    },
+    Recipe {
+        output: Item::WateringCan,
+        description: "Watered plants grow twice as fast",
+        ingredients: &[(Item::Scrap, 3)],
+    },
+    Recipe {
+        output: Item::Scarecrow,
+        description: "Keeps birds away from the field",
+        ingredients: &[(Item::Wood, 3), (Item::CropWaste, 2)],
+    },
+    Recipe {
+        output: Item::Fence,
+        description: "Stops raiders from trampling the field",
+        ingredients: &[(Item::Wood, 4)],
+    },
+    Recipe {
+        output: Item::Barricade,
+        description: "Helps holding out against raiders",
+        ingredients: &[(Item::Wood, 3), (Item::Scrap, 2)],
+    },
+    Recipe {
+        output: Item::Greenhouse,
+        description: "Plants next to it keep growing in winter",
+        ingredients: &[(Item::Wood, 6), (Item::Scrap, 6)],
+    },
+    Recipe {
+        output: Item::RootCellar,
+        description: "Food spoils a lot slower",
+        ingredients: &[(Item::Wood, 8), (Item::Scrap, 2)],
+    },
+    Recipe {
+        output: Item::Well,
+        description: "Waters the plants next to it every day",
+        ingredients: &[(Item::Wood, 4), (Item::Scrap, 5)],
+    },
+];
+
+pub struct Recipe {
+    pub output: Item,
+    pub description: &'static str,
+    pub ingredients: &'static [(Item, usize)],
+}
+
+impl Recipe {
+    /// Tools only need to be crafted once
+    fn is_available(&self, inventory: &Inventory) -> bool {
+        !self.output.is_tool() || inventory.count(self.output) == 0
+    }
+
+    fn can_craft(&self, inventory: &Inventory) -> bool {
+        self.ingredients
+            .iter()
+            .all(|(item, count)| inventory.count(*item) >= *count)
+    }
+
+    fn craft(&self, inventory: &mut Inventory) {
+        for (item, count) in self.ingredients {
+            inventory.remove(*item, *count);
+        }
+        inventory.add(self.output, 1);
+    }
+
+    fn ingredient_list(&self) -> String {
+        self.ingredients
+            .iter()
+            .map(|(item, count)| format!("{} x {}", count, item.name()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The recipes that can still be crafted, in the order they're listed
+fn available_recipes(inventory: &Inventory) -> impl Iterator<Item = &'static Recipe> + '_ {
+    RECIPES.iter().filter(|r| r.is_available(inventory))
+}
+
+/// Turning materials from the inventory into tools and structures
+#[derive(Default)]
+pub struct CraftingScreen {
+    crafted: Option<Item>,
+    effects: Effects,
+}
+
+impl CraftingScreen {
+    /// Where the recipe at `index` is listed, in two columns so all the recipes fit on the screen
+    fn position(index: usize) -> (f32, f32) {
+        (
+            50. + (index / RECIPES_PER_COLUMN) as f32 * 370.,
+            130. + (index % RECIPES_PER_COLUMN) as f32 * 70.,
+        )
+    }
+}
+
+impl Scene for CraftingScreen {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        let inventory = &mut ctx.state_mut().inventory;
+        if let Some(num_pressed) = get_num_pressed() {
+            let recipe = num_pressed
+                .checked_sub(1)
+                .and_then(|index| available_recipes(inventory).nth(index).map(|r| (index, r)));
+            if let Some((index, recipe)) = recipe {
+                if recipe.can_craft(inventory) {
+                    recipe.craft(inventory);
+                    self.crafted = Some(recipe.output);
+                    let (x, y) = Self::position(index);
+                    self.effects
+                        .text(format!("+1 {}", recipe.output.name()), x + 150., y, YELLOW);
+                }
+            }
+        }
+
+        if is_key_pressed(KeyCode::K) {
+            Transition::Pop
+        } else if is_key_pressed(KeyCode::Escape) {
+            Transition::push(PauseMenu::default())
+        } else {
+            Transition::Stay
+        }
+    }
+
+    fn draw(&mut self, ctx: &Ctx) {
+        let inventory = &ctx.state().inventory;
+        draw_text("Crafting", 50., 50., 40., WHITE);
+
+        for (index, recipe) in available_recipes(inventory).enumerate() {
+            let (x, y) = Self::position(index);
+            let color = if recipe.can_craft(inventory) {
+                WHITE
+            } else {
+                DARKGRAY
+            };
+            draw_text(
+                &format!("<{}> {}", index + 1, recipe.output.name()),
+                x,
+                y,
+                30.,
+                color,
+            );
+            draw_text(recipe.description, x + 30., y + 24., 20., GRAY);
+            draw_text(&recipe.ingredient_list(), x + 30., y + 44., 20., color);
+        }
+
+        if let Some(item) = self.crafted {
+            draw_text(
+                &format!("Crafted: {}", item.name()),
+                50.,
+                screen_height() - 90.,
+                24.,
+                YELLOW,
+            );
+        }
+
+        self.effects.draw((0., 0.));
+        draw_text("<K> back", 50., screen_height() - 50., 24., WHITE);
+    }
+}
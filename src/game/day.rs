@@ -0,0 +1,225 @@
+use super::{
+    next_event, scavenge, CraftingScreen, ExpectedChange, Location, MapScreen, Season, State,
+    RADIATION_SICKNESS,
+};
+use crate::{
+    dialogue::JournalScreen,
+    pause::PauseMenu,
+    scene::{Ctx, GameOver, Scene, Transition},
+};
+use macroquad::prelude::*;
+
+/// The day view, where the player decides what to do after the diary page of the morning
+#[derive(Default)]
+pub struct Day {
+    /// The page the view is showing, a new morning starts once the game moves past it
+    page: Option<u32>,
+}
+
+impl Day {
+    /// The day view of a game that is already past the morning, like one that was just loaded
+    pub fn resume(state: &State) -> Self {
+        Self {
+            page: Some(state.page),
+        }
+    }
+
+    /// Handle the keys of the day view
+    fn act(&mut self, state: &mut State) -> Transition {
+        let can_act = !state.has_a_headache;
+        if state.inventory.has_cookables() && can_act && is_key_pressed(KeyCode::C) {
+            return Transition::push(Activity::new(Box::new(state.cook())));
+        }
+        if can_act && is_key_pressed(KeyCode::K) {
+            return Transition::push(CraftingScreen::default());
+        }
+        if !state.journal.is_empty() && is_key_pressed(KeyCode::J) {
+            return Transition::push(JournalScreen::new(&state.journal));
+        }
+        if can_act && is_key_pressed(KeyCode::M) {
+            return Transition::push(MapScreen);
+        }
+        if can_act && is_key_pressed(KeyCode::S) {
+            return Transition::push(scavenge(state));
+        }
+        if is_key_pressed(KeyCode::Enter) {
+            if state.can_farm() && can_act {
+                return Transition::push(Activity::new(Location::Barn.visit(state)));
+            }
+            state.end_of_day();
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return Transition::push(PauseMenu::with_save(state.to_save()));
+        }
+        Transition::Stay
+    }
+}
+
+impl Scene for Day {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        let assets = ctx.assets;
+        let state = ctx.state_mut();
+        if self.page == Some(state.page) && !state.is_dead {
+            let transition = self.act(state);
+            if !matches!(transition, Transition::Stay) {
+                return transition;
+            }
+        }
+        if state.is_dead {
+            return Transition::replace(GameOver { days: state.page });
+        }
+        if self.page != Some(state.page) {
+            self.page = Some(state.page);
+            return Transition::Push(next_event(state).scene(state, assets));
+        }
+        Transition::Stay
+    }
+
+    fn draw(&mut self, ctx: &Ctx) {
+        let state = ctx.state();
+        if self.page != Some(state.page) {
+            // the diary page of the next morning opens before there's anything to do
+            return;
+        }
+        let x = 50.;
+        let mut y = 50.;
+        draw_text(
+            &format!("Day {}, {}", state.page, Season::of(state.page).name()),
+            x,
+            y,
+            40.,
+            WHITE,
+        );
+        y += 80.;
+
+        y += state
+            .health
+            .draw_if_not_full("Health", x, y, ExpectedChange::Unknown);
+        y += state.food.draw_if_not_full(
+            "Food",
+            x,
+            y,
+            if state.inventory.has_edibles() {
+                ExpectedChange::Increasing
+            } else {
+                ExpectedChange::Decreasing
+            },
+        );
+
+        if let Some(gun) = &state.gun {
+            y += gun
+                .condition
+                .draw_if_not_full("Gun", x, y, ExpectedChange::Unknown);
+        }
+
+        if let Some(_cat) = state.cat.get() {
+            draw_text("Cat is happy", x, y, 24., WHITE);
+            y += 30.;
+        }
+
+        for npc in state.npcs.met() {
+            draw_text(&format!("{} {}", npc.name(), npc.mood()), x, y, 24., WHITE);
+            y += 30.;
+        }
+
+        if state.radiation > RADIATION_SICKNESS {
+            draw_text("Radiation sickness", x, y, 24., RED);
+            y += 30.;
+        } else if state.radiation > 0 {
+            draw_text("Skin is tingling", x, y, 24., YELLOW);
+            y += 30.;
+        }
+
+        if !state.quests.is_empty() {
+            draw_text("Quests", x, y, 30., WHITE);
+            y += 40.;
+
+            for quest in &state.quests {
+                let days_left = quest.days_left(state.page);
+                draw_text(
+                    &format!(
+                        "{}: {} ({} day{} left)",
+                        quest.giver.name(state),
+                        quest.objective.description(),
+                        days_left,
+                        if days_left == 1 { "" } else { "s" }
+                    ),
+                    x,
+                    y,
+                    24.,
+                    if days_left <= 1 { ORANGE } else { WHITE },
+                );
+                y += 24.
+            }
+            y += 10.;
+        }
+
+        if state.inventory.has_items() {
+            draw_text("Inventory", x, y, 30., WHITE);
+            y += 40.;
+
+            for (item, count) in state.inventory.items() {
+                if count == 1 {
+                    draw_text(item.name(), x, y, 24., WHITE);
+                } else {
+                    draw_text(&format!("{}: {}", item.name(), count), x, y, 24., WHITE);
+                }
+                y += 24.
+            }
+        }
+
+        draw_text("<Esc> menu", 50., screen_height() - 50., 24., WHITE);
+        let can_act = !state.has_a_headache;
+        if state.inventory.has_cookables() && can_act {
+            draw_text("<C> cook", 450., screen_height() - 50., 24., WHITE);
+        }
+        if can_act {
+            draw_text("<K> craft", 450., screen_height() - 80., 24., WHITE);
+        }
+        if !state.journal.is_empty() {
+            draw_text("<J> journal", 600., screen_height() - 80., 24., WHITE);
+        }
+        if can_act {
+            draw_text("<M> map", 600., screen_height() - 50., 24., WHITE);
+            draw_text("<S> scavenge", 600., screen_height() - 110., 24., WHITE);
+        }
+        if state.can_farm() && can_act {
+            draw_text("<Enter> tend farm", 200., screen_height() - 50., 24., WHITE);
+        } else {
+            draw_text("<Enter> Next day", 200., screen_height() - 50., 24., WHITE);
+        }
+    }
+}
+
+/// Spending the rest of the day on something. The day ends once its screen is closed.
+pub struct Activity {
+    scene: Option<Box<dyn Scene>>,
+}
+
+impl Activity {
+    pub fn new(scene: Box<dyn Scene>) -> Self {
+        Self { scene: Some(scene) }
+    }
+}
+
+impl Scene for Activity {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        match self.scene.take() {
+            Some(scene) => Transition::Push(scene),
+            None => {
+                let state = ctx.state_mut();
+                if !state.is_dead {
+                    state.end_of_day();
+                }
+                Transition::Pop
+            }
+        }
+    }
+
+    fn draw(&mut self, ctx: &Ctx) {
+        // the activity is only on top for the frame it starts in, which should already show it
+        if let Some(scene) = &mut self.scene {
+            scene.draw(ctx);
+        }
+    }
+}
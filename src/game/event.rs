@@ -1,17 +1,27 @@
-use super::{Cat, CatState, Farm, Item, State, Tile};
-use crate::dialogue::{Dialogue, DialogueBuilder, Prompt};
-use macroquad::prelude::{DARKGREEN, RED, YELLOW};
+use super::{
+    has_active_quest, npc_visit, raid, resolve_quest, Cat, CatState, Farm, Gun, Item, Location,
+    Quest, QuestGiver, State, Tile,
+};
+use crate::{
+    assets::Assets,
+    dialogue::{Dialogue, DialogueBuilder, Prompt},
+    scene::{Scene, Transition},
+};
+use macroquad::prelude::{DARKGREEN, YELLOW};
 use rand::Rng;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Event {
     Visitor(Visitor),
     UnlockFarm,
+    UnlockTown,
     Headache,
     Raiders,
     CatVisit,
     Cold,
     Mice,
+    /// The giver of a quest in `State::quests` comes back, by index
+    QuestDue(usize),
     Nothing,
 }
 
@@ -19,117 +29,211 @@ pub enum Event {
 pub enum Visitor {
     OldFriend,
     Trader,
+    /// A recurring character from `State::npcs`, by index
+    Npc(usize),
+}
+
+/// What the player chose to do with the trader
+enum Trade {
+    Nothing,
+    Quest,
+    Seeds,
+    Gun,
+    Ammo,
+    Repair,
 }
 
 impl Event {
-    pub async fn dialogue(&self, state: &mut State) {
+    /// The diary page about the event. The page can already change the game, or only once the
+    /// player made a choice.
+    pub fn scene(&self, state: &mut State, assets: &Assets) -> Box<dyn Scene> {
         match self {
             Event::Cold => {
                 if state.has_a_cold {
                     if state.rng.gen_bool(0.5) {
-                        Dialogue::show(|d| {
+                        state.has_a_cold = false;
+                        Box::new(Dialogue::new(|d| {
                             d.page(state.page);
                             d.text("My sinuses are all cleared up this morning!");
-                        })
-                        .await;
-                        state.has_a_cold = false;
+                        }))
                     } else {
-                        Dialogue::show(|d| {
+                        Box::new(Dialogue::new(|d| {
                             d.page(state.page);
                             d.text("I still can't breathe.");
                             d.text("I hope this cold is over soon.");
-                        })
-                        .await;
+                        }))
                     }
                 } else {
-                    Dialogue::show(|d| {
+                    state.has_a_cold = true;
+                    Box::new(Dialogue::new(|d| {
                         d.page(state.page);
                         d.text("Woke up this morning and my sinuses are all clogged up.");
                         d.text("Must've caught a cold last night.");
                         d.text("I don't know if I can work today...");
-                    })
-                    .await;
-                    state.has_a_cold = true;
+                    }))
                 }
             }
             Event::Visitor(Visitor::OldFriend) => {
-                Dialogue::show(|d| {
+                let dialogue = Dialogue::new(|d| {
                     d.page(state.page);
                     d.text("I went back to my barn.");
                     d.text("I saw Greg!");
+                    d.say(
+                        "Greg",
+                        assets.portrait("greg"),
+                        "You made it! I was starting to think the bombs got you.",
+                    );
                     d.text("We shared some stories.");
                     d.text("");
                     d.text("He gave me some potato seeds.");
                     d.text("Maybe these will come in handy.");
                     d.color_text("Got 10 seeds", YELLOW);
-                })
-                .await;
+                    d.text("");
+                    d.text("He said I should come by his house some time.");
+                    d.color_text("Unlocked Greg's house!", YELLOW);
+                });
                 state.inventory.add(Item::Seeds, 10);
+                state.map.unlock(Location::GregsHouse);
+                if let Some(greg) = state.npcs.find_mut("greg") {
+                    greg.meet(state.page);
+                }
+                Box::new(dialogue)
             }
+            Event::Visitor(Visitor::Npc(index)) => Box::new(npc_visit(state, *index, assets)),
             Event::Visitor(Visitor::Trader) => {
                 let potatoes = state.inventory.count(Item::CookedPotato);
-                let choice = Prompt::show(|d| {
+                let has_gun = state.gun.is_some();
+                let gun_is_worn = state
+                    .gun
+                    .as_ref()
+                    .map(|gun| !gun.condition.is_max())
+                    .unwrap_or_default();
+                let quest = if potatoes < 10 && !has_active_quest(state, QuestGiver::Trader) {
+                    Some(Quest::for_trader(state))
+                } else {
+                    None
+                };
+                let prompt = Prompt::new(|d| {
                     d.page(state.page);
                     d.text("A trader showed up today.");
+                    d.say(
+                        "Trader",
+                        assets.portrait("trader"),
+                        "Potatoes are worth more than money these days, friend.",
+                    );
                     if potatoes < 10 {
                         d.text("But I didn't have enough...");
-                        d.skippable();
+                        match &quest {
+                            Some(quest) => {
+                                d.text(format!(
+                                    "He said he'd be back in {} days, and if I could {}, he'd give me {} x {}.",
+                                    quest.days_left(state.page),
+                                    quest.objective.description(),
+                                    quest.reward.1,
+                                    quest.reward.0.name()
+                                ));
+                                d.add_numbered_option(0, "decline", Trade::Nothing)
+                                    .text("I told him not to bother coming back.");
+                                d.add_option("accept", Trade::Quest)
+                                    .text("I told him I'd have it ready.");
+                            }
+                            None => d.skippable(Trade::Nothing),
+                        }
                         return;
                     }
-                    d.add_numbered_option(0, "don't trade")
+                    d.add_numbered_option(0, "don't trade", Trade::Nothing)
                         .text("But I didn't feel like trading.");
-                    if potatoes > 10 {
-                        d.add_option("10 potato seeds for 10 cooked potatoes")
+                    d.add_branch("see what he has", |d| {
+                        d.text("He laid out everything he had on a blanket.");
+                        d.add_numbered_option(0, "never mind", Trade::Nothing)
+                            .text("Nothing caught my eye.");
+                        d.add_option("10 potato seeds for 10 cooked potatoes", Trade::Seeds)
+                            .enabled_if(potatoes >= 10, "not enough potatoes")
                             .text("I traded some potatoes for some seeds.")
                             .text("Time to plant some more I guess.");
-                    }
-                    if potatoes > 500 {
-                        d.add_option("a gun for 500 potatoes")
-                            .text("He had a gun for trade, but wanted a huge amount of potatoes")
-                            .text("Long story short I can defend myself now.");
-                    }
-                })
-                .await;
-                match choice {
-                    1 => {
-                        if !state.inventory.try_remove(Item::CookedPotato, 10) {
-                            eprintln!("Could not buy; not enough potatoes");
+                        if has_gun {
+                            d.add_option("6 bullets for 30 potatoes", Trade::Ammo)
+                                .enabled_if(potatoes >= 30, "not enough potatoes")
+                                .text("I stocked up on bullets.")
+                                .text("Hopefully I won't need them.");
+                            d.add_option("fix up the gun for 50 potatoes", Trade::Repair)
+                                .enabled_if(gun_is_worn, "the gun is fine")
+                                .enabled_if(potatoes >= 50, "not enough potatoes")
+                                .text("He cleaned and oiled my gun.")
+                                .text("Good as new.");
+                        } else {
+                            d.add_option("a gun for 500 potatoes", Trade::Gun)
+                                .enabled_if(potatoes >= 500, "not enough potatoes")
+                                .text(
+                                    "He had a gun for trade, but wanted a huge amount of potatoes",
+                                )
+                                .text("Long story short I can defend myself now.")
+                                .color_text("Got a gun and 6 bullets", YELLOW);
                         }
-                        state.inventory.add(Item::Seeds, 10);
-                    }
-                    2 => {
-                        if !state.inventory.try_remove(Item::CookedPotato, 500) {
-                            eprintln!("Could not buy; not enough potatoes");
+                    });
+                });
+                Box::new(prompt.then(move |trade, ctx| {
+                    let state = ctx.state_mut();
+                    match trade {
+                        Trade::Quest => state.quests.extend(quest),
+                        Trade::Seeds => {
+                            if state.inventory.try_remove(Item::CookedPotato, 10) {
+                                state.inventory.add(Item::Seeds, 10);
+                            } else {
+                                eprintln!("Could not buy; not enough potatoes");
+                            }
+                        }
+                        Trade::Gun => {
+                            if state.inventory.try_remove(Item::CookedPotato, 500) {
+                                state.gun = Some(Gun::default());
+                                state.inventory.add(Item::Ammo, 6);
+                            } else {
+                                eprintln!("Could not buy; not enough potatoes");
+                            }
+                        }
+                        Trade::Ammo => {
+                            if state.inventory.try_remove(Item::CookedPotato, 30) {
+                                state.inventory.add(Item::Ammo, 6);
+                            } else {
+                                eprintln!("Could not buy; not enough potatoes");
+                            }
+                        }
+                        Trade::Repair => {
+                            if state.inventory.try_remove(Item::CookedPotato, 50) {
+                                state.gun = Some(Gun::default());
+                            } else {
+                                eprintln!("Could not repair; not enough potatoes");
+                            }
                         }
-                        state.inventory.add(Item::Gun, 1);
+                        Trade::Nothing => {}
                     }
-                    _ => {}
-                }
+                    Transition::Pop
+                }))
             }
+            Event::QuestDue(index) => Box::new(resolve_quest(state, *index)),
             Event::Nothing => {
-                Dialogue::show(|d| {
+                let dialogue = Dialogue::new(|d| {
                     d.page(state.page);
                     d.text("I had an uneventful sleep.");
                     d.text("How refreshing.");
                     if state.food.is_max() && !state.health.is_max() {
                         d.color_text("Regained some health", DARKGREEN);
                     }
-                })
-                .await;
+                });
                 state.health.add(1);
+                Box::new(dialogue)
             }
             Event::Mice => {
-                Dialogue::show(|d| {
+                let dialogue = Dialogue::new(|d| {
                     d.page(state.page);
                     if state.cat.get().is_some() {
                         d.text("I saw the cat play with some dead mice this morning.");
-                        d.text("Disgusting.");
+                        d.rich_text("<pause>Disgusting.");
                     } else {
-                        d.jiggle_color_text("Some of my potatoes have been eaten by mice!", RED);
+                        d.rich_text("Some of my potatoes have been <red><jiggle>eaten by mice!</jiggle></red>");
                         d.text("This is a disaster...");
                     }
-                })
-                .await;
+                });
                 if state.cat.get().is_none() {
                     if let Some(farm) = state.farm.as_mut() {
                         farm.for_each(|_, _, tile| {
@@ -141,105 +245,67 @@ impl Event {
                         });
                     }
                 }
+                Box::new(dialogue)
             }
             Event::Headache => {
-                Dialogue::show(|d| {
+                state.has_a_headache = true;
+                Box::new(Dialogue::new(|d| {
                     d.page(state.page);
                     d.text("Woke up with a massive headache.");
                     d.text("Not going to be able to work today.");
                     d.text("");
                     d.text("The worst part about a nuclear war is the lack of painkillers.");
-                })
-                .await;
+                }))
             }
             Event::CatVisit => {
-                let result = Prompt::show(|p| {
+                let prompt = Prompt::new(|p| {
                     p.page(state.page);
                     p.text("I had a visit of a cute cat this morning.");
                     p.text("He seemed to like me.");
 
-                    p.add_option("take the cat in.")
+                    p.add_option("take the cat in.", true)
                         .text("I decided to take the cat in.")
                         .text("He seems to like the fireplace.");
-                    p.add_option("chase the cat off.")
+                    p.add_option("chase the cat off.", false)
                         .text("Momma always said that cats brought bad omens.")
                         .text("I don't think that cat is going to be back.");
-                })
-                .await;
-
-                if result == 1 {
-                    state.cat = CatState::Cat(Cat::default());
-                } else {
-                    state.cat = CatState::None;
-                }
-            }
-            Event::Raiders => {
-                let has_gun = state.inventory.count(Item::Gun) > 0;
-                let potato_count = state.inventory.count(Item::CookedPotato);
-                let requested = if state.cat.get().is_some() { 100 } else { 70 };
-                let damage = 30;
-                let result = Prompt::show(|p| {
-                    p.page(state.page);
-                    p.text("Raiders came in last night demanding food.");
-                    if has_gun {
-                        p.text("Luckily I had that gun.");
-                        p.text("I pointed it at them and they got scared.");
-                        p.text("You should've seen their faces.");
-                        p.skippable();
-                        return;
-                    }
-                    if state.cat.get().is_some() {
-                        p.text("They even threatened to kill my cat if I didn't comply.");
-                    }
-                    if potato_count < requested {
-                        p.text(format!(
-                            "They demanded {} potatoes, I didn't have that many...",
-                            requested
-                        ));
-                    }
-                    if state.health.current <= damage {
-                        p.add_option("refuse")
-                            .color_text("They shoot you. You die.", RED);
+                });
+                Box::new(prompt.then(|take_in, ctx| {
+                    let state = ctx.state_mut();
+                    if take_in {
+                        state.cat = CatState::Cat(Cat::default());
                     } else {
-                        p.add_option("refuse")
-                            .color_text("Those bastards shot me", RED)
-                            .color_text("<Lost health>", RED);
+                        state.cat = CatState::None;
                     }
-                    if state.inventory.count(Item::CookedPotato) >= requested {
-                        p.add_option(format!("Give {} potatoes", requested))
-                            .text("I had no choice but to give them the potatoes");
-                    }
-                })
-                .await;
-                match result {
-                    0 if has_gun => {}
-                    1 => {
-                        if !state.health.subn(damage) {
-                            state.is_dead = true;
-                        }
-                    }
-                    2 => {
-                        state.inventory.remove(Item::CookedPotato, requested);
-                    }
-                    _ => unreachable!(),
-                }
+                    Transition::Pop
+                }))
             }
+            Event::Raiders => Box::new(raid(state)),
             Event::UnlockFarm => {
-                Dialogue::show(|d| {
+                let dialogue = Dialogue::new(|d| {
                     d.page(state.page);
                     d.text("I'm so tired of sitting inside all day.");
                     d.text("And my food is starting to get low.");
                     d.text("");
                     d.text("I should go farm some potatoes.");
                     d.jiggle_color_text("Unlocked farm!", YELLOW);
-                })
-                .await;
+                });
                 state.farm = Some(Farm::default());
+                state.map.unlock(Location::Barn);
+                Box::new(dialogue)
+            }
+            Event::UnlockTown => {
+                let dialogue = Dialogue::new(|d| {
+                    d.page(state.page);
+                    d.text("The sky was clear enough today to see all the way to town.");
+                    d.text("Some of the buildings are still standing.");
+                    d.text("");
+                    d.text("There might be supplies left, if I'm brave enough to go look.");
+                    d.jiggle_color_text("Unlocked town!", YELLOW);
+                });
+                state.map.unlock(Location::Town);
+                Box::new(dialogue)
             }
         }
     }
-
-    pub fn can_execute_action(&self) -> bool {
-        !matches!(self, Event::Headache)
-    }
 }
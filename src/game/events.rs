@@ -1,6 +1,10 @@
 use super::{Event, Item, State, Visitor};
 use rand::{thread_rng, Rng};
 
+/// Chance for a character who is due to visit to show up, rolled along with the other events. If
+/// they don't, they come on a later day.
+const NPC_VISIT_CHANCE: f64 = 0.5;
+
 static EVENTS: &[E] = &[
     E {
         event: Event::Visitor(Visitor::OldFriend),
@@ -12,6 +16,11 @@ static EVENTS: &[E] = &[
         condition: |state| state.day_delta() == 2,
         chance: 1.0,
     },
+    E {
+        event: Event::UnlockTown,
+        condition: |state| state.day_delta() == 4,
+        chance: 1.0,
+    },
     // cold event will always trigger when we have a cold
     E {
         event: Event::Cold,
@@ -35,7 +44,10 @@ static EVENTS: &[E] = &[
     },
     E {
         event: Event::Raiders,
-        condition: |s| s.inventory.count(Item::CookedPotato) > 50,
+        condition: |s| {
+            s.raiders
+                .is_interested(s.page, s.inventory.count(Item::CookedPotato))
+        },
         chance: 0.1,
     },
     E {
@@ -57,6 +69,10 @@ pub struct E {
 }
 
 pub fn next_event(state: &State) -> Event {
+    if let Some(index) = state.quests.iter().position(|q| q.deadline <= state.page) {
+        return Event::QuestDue(index);
+    }
+
     let mut rng = thread_rng();
     // try rolling 3 times
     for _ in 0..3 {
@@ -65,6 +81,11 @@ pub fn next_event(state: &State) -> Event {
                 return event.event;
             }
         }
+        if let Some(index) = state.npcs.due_visitor(state.page) {
+            if rng.gen_bool(NPC_VISIT_CHANCE) {
+                return Event::Visitor(Visitor::Npc(index));
+            }
+        }
     }
 
     Event::Nothing
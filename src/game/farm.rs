@@ -1,29 +1,89 @@
 use crate::{
     assets::Assets,
     draw_text_centered,
-    game::{Item, State},
+    effects::Effects,
+    game::{
+        Compost, ExpectedChange, Inventory, Item, Pest, PestKind, Season, Stat, State, Structure,
+    },
+    pause::PauseMenu,
+    scene::{Ctx, Scene, Transition},
 };
 use ::rand::Rng;
 use macroquad::prelude::*;
 
-const SIZE: usize = 10;
-const POTATO_MATURE_AGE: u8 = 5;
+/// Size of the cleared field on a new farm
+const START_SIZE: usize = 10;
+/// The farm won't grow beyond this many tiles in either direction
+pub const MAX_SIZE: usize = 20;
+/// Days it takes to clear an overgrown tile
+pub const CLEARING_DAYS: u8 = 2;
+pub const POTATO_MATURE_AGE: u8 = 5;
 const TILE_PX: f32 = 50.;
 const PLAYER_SPEED: f32 = 2.;
 const PLAYER_SICK_SPEED: f32 = 0.5;
 const TOUCH_DISTANCE: f32 = 30.;
 const TOUCH_RANGE: f32 = 60.;
 const FARM_START: (usize, usize) = (4, 1);
+/// Days before an infected plant starts showing symptoms
+const BLIGHT_INCUBATION: u8 = 3;
+/// Days after which an infected plant dies
+pub const BLIGHT_FATAL: u8 = 9;
+/// Chance per day for an infected plant to infect each of its neighbours
+const BLIGHT_SPREAD_CHANCE: f64 = 0.1;
+/// Spread chance once the infection is showing symptoms
+const BLIGHT_SYMPTOMATIC_SPREAD_CHANCE: f64 = 0.25;
+const BLIGHT_OUTBREAK_CHANCE: f64 = 0.1;
+pub const MAX_FERTILITY: u8 = 10;
+const START_FERTILITY: u8 = 8;
+/// Fertility lost every time a potato is harvested from a tile
+const HARVEST_DEPLETION: u8 = 3;
+/// Fertility regained every day a tile lies fallow
+const FALLOW_RECOVERY: u8 = 1;
+/// Fertility added by a bag of fertilizer
+const FERTILIZER_BOOST: u8 = 5;
+/// Chance for the cat to chase off each crow on the farm
+const CAT_CATCH_CHANCE: f64 = 0.5;
+/// Chance per day for beetles to multiply onto a neighbouring plant, while the ones that were
+/// there stay put
+const BEETLE_SPREAD_CHANCE: f64 = 0.3;
+/// Pixels the farmer can walk for a single point of energy
+const WALK_PER_ENERGY: f32 = 50.;
+const PLANT_ENERGY: u32 = 2;
+const HARVEST_ENERGY: u32 = 3;
+const CLEAR_ENERGY: u32 = 15;
+const UPROOT_ENERGY: u32 = 2;
+const WATER_ENERGY: u32 = 1;
+const FERTILIZE_ENERGY: u32 = 2;
+const BUILD_ENERGY: u32 = 10;
+const PEST_ENERGY: u32 = 2;
+const COMPOST_ENERGY: u32 = 2;
+/// The farm day runs from sunrise until the farmer runs out of energy at sundown
+const DAY_START_HOUR: f32 = 6.;
+const DAY_END_HOUR: f32 = 20.;
+/// How long a message above the farmer stays visible, in seconds
+const MESSAGE_DURATION: f64 = 2.;
 
+/// The field, indexed as `[x][y]`. The right and bottom edges are overgrown land that can be cleared
+/// to make the farm bigger.
 pub struct Farm {
-    pub tiles: [[Tile; SIZE]; SIZE],
+    pub tiles: Vec<Vec<Tile>>,
+    /// Nutrients left in the soil of each tile, up to `MAX_FERTILITY`
+    pub fertility: Vec<Vec<u8>>,
+    /// Tiles the farmer watered today
+    pub watered: Vec<Vec<bool>>,
+    pub compost: Compost,
+    pub pests: Vec<Pest>,
     pub days_since_last_blight: u32,
 }
 
 impl Default for Farm {
     fn default() -> Self {
         let mut farm = Self {
-            tiles: Default::default(),
+            tiles: vec![vec![Tile::Dirt; START_SIZE]; START_SIZE],
+            fertility: vec![vec![START_FERTILITY; START_SIZE]; START_SIZE],
+            watered: vec![vec![false; START_SIZE]; START_SIZE],
+            compost: Compost::default(),
+            pests: Vec::new(),
             days_since_last_blight: 0,
         };
         let potatoes = [
@@ -42,206 +102,319 @@ impl Default for Farm {
             (9, 2, 3),
         ];
         for (x, y, age) in potatoes {
-            farm.tiles[x][y] = Tile::Potato { age, blight: false };
+            farm.tiles[x][y] = Tile::Potato { age, blight: None };
         }
+        farm.add_column();
+        farm.add_row();
         farm
     }
 }
 
 impl Farm {
-    pub fn end_of_day(&mut self, rng: &mut impl Rng) {
-        let mut has_blight = false;
-        for x in 0..SIZE {
-            for y in 0..SIZE {
-                let tile = &mut self.tiles[x][y];
-                if let Tile::Potato { age, blight } = tile {
-                    if *age < POTATO_MATURE_AGE {
-                        *age += 1;
-                    }
-                    if *blight {
-                        has_blight = true;
-                        self.around_mut(x, y, |tile| {
-                            if let Tile::Potato { blight, .. } = tile {
-                                *blight = true
-                            }
-                        });
-                    }
+    pub fn width(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.tiles.first().map(Vec::len).unwrap_or_default()
+    }
+
+    /// Add a column of overgrown land on the right side of the farm
+    fn add_column(&mut self) {
+        let height = self.height();
+        self.tiles.push(vec![Tile::Overgrown; height]);
+        // land that has never been farmed is rich
+        self.fertility.push(vec![MAX_FERTILITY; height]);
+        self.watered.push(vec![false; height]);
+    }
+
+    /// Add a row of overgrown land at the bottom of the farm
+    fn add_row(&mut self) {
+        for x in 0..self.width() {
+            self.tiles[x].push(Tile::Overgrown);
+            self.fertility[x].push(MAX_FERTILITY);
+            self.watered[x].push(false);
+        }
+    }
+
+    pub fn end_of_day(&mut self, season: Season, has_cat: bool, rng: &mut impl Rng) {
+        self.compost.end_of_day();
+        for x in 0..self.width() {
+            for y in 0..self.height() {
+                if let Tile::Dirt = self.tiles[x][y] {
+                    self.fertilize(x, y, FALLOW_RECOVERY);
                 }
             }
         }
-        if !has_blight {
-            self.days_since_last_blight += 1;
-        } else {
-            self.days_since_last_blight = 0;
-        }
 
-        if self.days_since_last_blight > 10 && rng.gen_bool(0.1) {
-            'blight_loop: for x in 0..SIZE {
-                for y in 0..SIZE {
-                    if let Tile::Potato { blight, .. } = &mut self.tiles[x][y] {
-                        *blight = true;
-                        break 'blight_loop;
+        let dry = vec![vec![false; self.height()]; self.width()];
+        let watered = std::mem::replace(&mut self.watered, dry);
+        let sheltered = self.coverage(Structure::Greenhouse);
+        let irrigated = self.coverage(Structure::Well);
+        let mut infected = Vec::new();
+        self.for_each(|x, y, tile| {
+            if let Tile::Potato { age, blight } = tile {
+                let growth = if !season.allows_growth() && !sheltered[x][y] {
+                    0
+                } else if watered[x][y] || irrigated[x][y] {
+                    2
+                } else {
+                    1
+                };
+                *age = (*age + growth).min(POTATO_MATURE_AGE);
+                if let Some(b) = blight {
+                    b.days += 1;
+                    if b.days >= BLIGHT_FATAL {
+                        *tile = Tile::Dirt;
+                    } else {
+                        infected.push((x, y, b.is_visible()));
                     }
                 }
             }
-        }
-    }
+        });
 
-    pub fn around_mut(&mut self, x: usize, y: usize, mut cb: impl FnMut(&mut Tile)) {
-        let min_x = if x == 0 { x } else { x - 1 };
-        let max_x = if x < SIZE - 1 { x + 1 } else { x };
-        let min_y = if y == 0 { y } else { y - 1 };
-        let max_y = if y < SIZE - 1 { y + 1 } else { y };
-
-        for tile_x in min_x..=max_x {
-            for tile_y in min_y..=max_y {
-                if tile_x != x && tile_y != y {
-                    cb(&mut self.tiles[tile_x][tile_y]);
+        // spread after aging, so plants infected today don't spread until tomorrow
+        for &(x, y, symptomatic) in &infected {
+            let chance = if symptomatic {
+                BLIGHT_SYMPTOMATIC_SPREAD_CHANCE
+            } else {
+                BLIGHT_SPREAD_CHANCE
+            };
+            self.around_mut(x, y, |tile| {
+                if let Tile::Potato { blight, .. } = tile {
+                    if blight.is_none() && rng.gen_bool(chance) {
+                        *blight = Some(Blight::default());
+                    }
                 }
-            }
+            });
         }
-    }
 
-    pub async fn draw(&mut self, state: &mut State, assets: &Assets) {
-        let mut px = 150.0;
-        let mut py = 50.0;
-        let mut facing = (0, 1);
-        let start_raw_potatoes =
-            state.inventory.count(Item::RawPotato) + state.inventory.count(Item::RawPotatoBlight);
-        let speed = if state.has_a_cold {
-            PLAYER_SICK_SPEED
+        if infected.is_empty() {
+            self.days_since_last_blight += 1;
         } else {
-            PLAYER_SPEED
-        };
-        loop {
-            clear_background(DARKGREEN);
-            for x in 0..SIZE {
-                for y in 0..SIZE {
-                    let tile = &self.tiles[x][y];
-                    tile.draw_at(
-                        (FARM_START.0 + x) as f32 * TILE_PX,
-                        (FARM_START.1 + y) as f32 * TILE_PX,
-                    );
+            self.days_since_last_blight = 0;
+        }
+
+        if self.days_since_last_blight > 10 && rng.gen_bool(BLIGHT_OUTBREAK_CHANCE) {
+            let mut healthy = Vec::new();
+            self.for_each(|x, y, tile| {
+                if let Tile::Potato { blight: None, .. } = tile {
+                    healthy.push((x, y));
+                }
+            });
+            if !healthy.is_empty() {
+                let (x, y) = healthy[rng.gen_range(0..healthy.len())];
+                if let Tile::Potato { blight, .. } = &mut self.tiles[x][y] {
+                    *blight = Some(Blight::default());
                 }
             }
-            draw_texture(assets.farm, 0., 0., WHITE);
-            draw_texture(
-                assets.farmer_front,
-                px - assets.farmer_front.width() / 2.0,
-                py - assets.farmer_front.height() / 2.0,
-                WHITE,
-            );
+        }
 
-            let mut dx = 0;
-            let mut dy = 0;
+        self.update_pests(season, has_cat, rng);
 
-            if is_key_down(KeyCode::D) || is_key_down(KeyCode::Right) {
-                dx += 1;
-            }
-            if is_key_down(KeyCode::A) || is_key_down(KeyCode::Left) {
-                dx -= 1;
+        let mut cleared = Vec::new();
+        self.for_each(|x, y, tile| {
+            if let Tile::Clearing { days_left } = tile {
+                *days_left -= 1;
+                if *days_left == 0 {
+                    *tile = Tile::Dirt;
+                    cleared.push((x, y));
+                }
             }
-            if is_key_down(KeyCode::S) || is_key_down(KeyCode::Down) {
-                dy += 1;
+        });
+        // clearing the edge of the farm reveals more overgrown land behind it
+        for (x, y) in cleared {
+            if x + 1 == self.width() && self.width() < MAX_SIZE {
+                self.add_column();
             }
-            if is_key_down(KeyCode::W) || is_key_down(KeyCode::Up) {
-                dy -= 1;
+            if y + 1 == self.height() && self.height() < MAX_SIZE {
+                self.add_row();
             }
+        }
+    }
 
-            if dx != 0 || dy != 0 {
-                facing = (dx, dy);
-                px += (dx as f32) * speed;
-                py += (dy as f32) * speed;
+    pub fn has_structure(&self, structure: Structure) -> bool {
+        self.tiles
+            .iter()
+            .flatten()
+            .any(|tile| matches!(tile, Tile::Structure(s) if *s == structure))
+    }
 
-                let min_x = if py < 170. { 10. } else { 0. } + 16.;
-                let min_y = if px < 70. { 10. } else { 0. } + 32.;
-                px = px.max(min_x).min(screen_width() - 16.);
-                py = py.max(min_y).min(screen_height() - 32.);
+    /// Which tiles are next to the given kind of structure
+    fn coverage(&self, structure: Structure) -> Vec<Vec<bool>> {
+        let mut positions = Vec::new();
+        for x in 0..self.width() {
+            for y in 0..self.height() {
+                if matches!(self.tiles[x][y], Tile::Structure(s) if s == structure) {
+                    positions.push((x, y));
+                }
+            }
+        }
+        let range = structure.range();
+        let mut covered = vec![vec![false; self.height()]; self.width()];
+        for (x, column) in covered.iter_mut().enumerate() {
+            for (y, covered) in column.iter_mut().enumerate() {
+                *covered = positions.iter().any(|&(sx, sy)| {
+                    (sx, sy) != (x, y) && sx.abs_diff(x) <= range && sy.abs_diff(y) <= range
+                });
             }
+        }
+        covered
+    }
 
-            let seed_count = state.inventory.count(Item::Seeds);
-            let raw_potato_count = state.inventory.count(Item::RawPotato)
-                + state.inventory.count(Item::RawPotatoBlight);
+    /// Let the pests on the farm do their damage, and see if new ones show up
+    fn update_pests(&mut self, season: Season, has_cat: bool, rng: &mut impl Rng) {
+        let (width, height) = (self.width(), self.height());
+        let scared = self.coverage(Structure::Scarecrow);
+        let is_target = |tiles: &[Vec<Tile>], kind: PestKind, x: usize, y: usize| {
+            kind.targets(&tiles[x][y]) && !(kind == PestKind::Crow && scared[x][y])
+        };
 
-            if state.has_a_cold {
-                draw_text("Sick", 10., screen_height() - 100., 40., SKYBLUE);
+        let pests = std::mem::take(&mut self.pests);
+        let mut remaining: Vec<Pest> = Vec::new();
+        for (index, &pest) in pests.iter().enumerate() {
+            // the cat goes after the birds
+            if pest.kind == PestKind::Crow && has_cat && rng.gen_bool(CAT_CATCH_CHANCE) {
+                continue;
             }
-            draw_text(
-                &format!("Seeds: {}", seed_count),
-                10.,
-                screen_height() - 70.,
-                24.,
-                WHITE,
-            );
-            draw_text(
-                &format!(
-                    "Potatoes: {}{}",
-                    if raw_potato_count > start_raw_potatoes {
-                        "+"
-                    } else {
-                        ""
-                    },
-                    raw_potato_count - start_raw_potatoes
-                ),
-                10.,
-                screen_height() - 50.,
-                24.,
-                WHITE,
-            );
+            if !is_target(&self.tiles, pest.kind, pest.x, pest.y) {
+                // nothing left to eat here, or a scarecrow went up
+                continue;
+            }
+            pest.damage(&mut self.tiles[pest.x][pest.y]);
 
-            if py < 150. && px < 64. {
-                draw_text_centered(
-                    "<Enter> end day",
-                    screen_width() / 2.0,
-                    screen_height() - 10.,
-                    40.,
-                    WHITE,
-                );
-                if is_key_pressed(KeyCode::Enter) {
-                    return;
+            // a tile holds a single pest, counting the ones that haven't moved yet
+            let occupied = |remaining: &[Pest], (x, y): (usize, usize)| {
+                remaining
+                    .iter()
+                    .chain(&pests[index + 1..])
+                    .any(|p| (p.x, p.y) == (x, y))
+            };
+            let nearby: Vec<_> = neighbours(pest.x, pest.y, width, height)
+                .filter(|&(x, y)| is_target(&self.tiles, pest.kind, x, y))
+                .filter(|&tile| !occupied(&remaining, tile))
+                .collect();
+            match pest.kind {
+                // crows hop over to the next seedling, or fly off when there's none left
+                PestKind::Crow => {
+                    if !nearby.is_empty() {
+                        let (x, y) = nearby[rng.gen_range(0..nearby.len())];
+                        remaining.push(Pest { x, y, ..pest });
+                    }
                 }
-            } else {
-                draw_text("<Esc> exit", 10., screen_height() - 10., 30., WHITE);
-                if is_key_pressed(KeyCode::Escape) {
-                    return;
+                PestKind::Beetle => {
+                    if !nearby.is_empty() && rng.gen_bool(BEETLE_SPREAD_CHANCE) {
+                        let (x, y) = nearby[rng.gen_range(0..nearby.len())];
+                        remaining.push(Pest { x, y, ..pest });
+                    }
+                    if is_target(&self.tiles, pest.kind, pest.x, pest.y)
+                        && !occupied(&remaining, (pest.x, pest.y))
+                    {
+                        remaining.push(pest);
+                    }
                 }
             }
+        }
 
-            if let Some((x, y, tile)) = self.get_hover_tile((px, py), facing, state) {
-                if let Some(action_name) = tile.action_name() {
-                    draw_rectangle_lines(
-                        (x + FARM_START.0) as f32 * TILE_PX,
-                        (y + FARM_START.1) as f32 * TILE_PX,
-                        TILE_PX,
-                        TILE_PX,
-                        2.0,
-                        RED,
-                    );
-                    draw_text_centered(
-                        &format!("<Enter> {}", action_name),
-                        screen_width() / 2.0,
-                        screen_height() - 10.,
-                        40.,
-                        WHITE,
-                    );
-                    if is_key_pressed(KeyCode::Enter) {
-                        self.execute(x, y, state);
+        for kind in [PestKind::Crow, PestKind::Beetle] {
+            if !rng.gen_bool(kind.spawn_chance(season)) {
+                continue;
+            }
+            let mut targets = Vec::new();
+            for x in 0..width {
+                for y in 0..height {
+                    if is_target(&self.tiles, kind, x, y)
+                        && !remaining.iter().any(|p| (p.x, p.y) == (x, y))
+                    {
+                        targets.push((x, y));
                     }
                 }
             }
+            for _ in 0..rng.gen_range(1..=3).min(targets.len()) {
+                let (x, y) = targets.swap_remove(rng.gen_range(0..targets.len()));
+                remaining.push(Pest { kind, x, y });
+            }
+        }
+        self.pests = remaining;
+    }
 
-            next_frame().await;
+    pub fn pest_at(&self, x: usize, y: usize) -> Option<PestKind> {
+        self.pests
+            .iter()
+            .find(|pest| (pest.x, pest.y) == (x, y))
+            .map(|pest| pest.kind)
+    }
+
+    /// Call `cb` for every tile that touches the given tile, including diagonals
+    pub fn around_mut(&mut self, x: usize, y: usize, mut cb: impl FnMut(&mut Tile)) {
+        for (tile_x, tile_y) in neighbours(x, y, self.width(), self.height()) {
+            cb(&mut self.tiles[tile_x][tile_y]);
+        }
+    }
+
+    /// The tiles the farmer can work on from where they stand
+    fn hover(&self, pos: (f32, f32), facing: (i32, i32), state: &State) -> Hover {
+        Hover {
+            plant: self.get_hover_tile(pos, facing, |_, _, tile| {
+                u8::from(matches!(tile, Tile::Potato { .. }))
+            }),
+            target: self.get_hover_tile(pos, facing, |x, y, tile| {
+                self.action_rank(x, y, tile, state)
+            }),
         }
     }
 
+    /// Draw a fence around the field as it is now, so it moves out when the field grows
+    fn draw_fence(&self, (cam_x, cam_y): (f32, f32)) {
+        let margin = TILE_PX / 4.;
+        draw_rectangle_lines(
+            FARM_START.0 as f32 * TILE_PX - margin - cam_x,
+            FARM_START.1 as f32 * TILE_PX - margin - cam_y,
+            self.width() as f32 * TILE_PX + margin * 2.,
+            self.height() as f32 * TILE_PX + margin * 2.,
+            4.,
+            BROWN,
+        );
+    }
+
+    /// Size of the farm screen in pixels, which can be bigger than the window
+    fn world_size(&self) -> (f32, f32) {
+        let width = (FARM_START.0 + self.width() + 1) as f32 * TILE_PX;
+        let height = (FARM_START.1 + self.height() + 1) as f32 * TILE_PX;
+        (width.max(screen_width()), height.max(screen_height()))
+    }
+
+    /// How much the farmer wants to do the `<Enter>` action on a tile: pests first, then mature
+    /// plants, then land to plant in or clear, then growing plants. Dirt without seeds to plant
+    /// still ranks like a growing plant, so it can be fertilized or built on.
+    fn action_rank(&self, x: usize, y: usize, tile: &Tile, state: &State) -> u8 {
+        let has_seeds = state.inventory.count(Item::Seeds) > 0;
+        let has_fertilizer = state.inventory.count(Item::Fertilizer) > 0;
+        let has_structure = state
+            .inventory
+            .items()
+            .any(|(item, _)| Structure::from_item(item).is_some());
+        match tile {
+            _ if self.pest_at(x, y).is_some() => 4,
+            Tile::Potato {
+                age: POTATO_MATURE_AGE,
+                ..
+            } => 3,
+            Tile::Dirt if has_seeds => 2,
+            Tile::Overgrown => 2,
+            Tile::Dirt if has_fertilizer || has_structure => 1,
+            Tile::Potato { .. } => 1,
+            Tile::Dirt | Tile::Clearing { .. } | Tile::Structure(_) => 0,
+        }
+    }
+
+    /// The tile within reach of the farmer with the highest `rank`, if any ranks above 0
     fn get_hover_tile(
-        &mut self,
+        &self,
         (px, py): (f32, f32),
         facing: (i32, i32),
-        state: &State,
-    ) -> Option<(usize, usize, &Tile)> {
-        let has_seeds = state.inventory.count(Item::Seeds) > 0;
-
+        rank: impl Fn(usize, usize, &Tile) -> u8,
+    ) -> Option<(usize, usize)> {
         let min_x = px + facing.0 as f32 * TOUCH_DISTANCE - TOUCH_RANGE / 2.;
         let min_y = py + facing.1 as f32 * TOUCH_DISTANCE - TOUCH_RANGE / 2.;
         let max_x = min_x + TOUCH_RANGE;
@@ -252,10 +425,10 @@ impl Farm {
         let max_x = (max_x / TILE_PX).ceil() as isize - FARM_START.0 as isize;
         let max_y = (max_y / TILE_PX).ceil() as isize - FARM_START.1 as isize;
 
-        let min_x = (min_x.max(0) as usize).min(SIZE);
-        let min_y = (min_y.max(0) as usize).min(SIZE);
-        let max_x = (max_x.max(0) as usize).min(SIZE);
-        let max_y = (max_y.max(0) as usize).min(SIZE);
+        let min_x = (min_x.max(0) as usize).min(self.width());
+        let min_y = (min_y.max(0) as usize).min(self.height());
+        let max_x = (max_x.max(0) as usize).min(self.width());
+        let max_y = (max_y.max(0) as usize).min(self.height());
 
         // draw_rectangle_lines(
         //     (min_x + FARM_START.0) as f32 * TILE_PX,
@@ -266,23 +439,14 @@ impl Farm {
         //     BLACK,
         // );
 
-        let mut most_significant: Option<(usize, usize, &Tile)> = None;
+        let mut most_significant: Option<(usize, usize)> = None;
+        let mut highest = 0;
         for x in min_x..max_x {
             for y in min_y..max_y {
-                let tile = &self.tiles[x][y];
-                let most_significant_tile = most_significant.as_ref().map(|(_x, _y, tile)| tile);
-                let replace = match (tile, most_significant_tile) {
-                    (Tile::Potato { age, .. }, None)
-                    | (Tile::Potato { age, .. }, Some(Tile::Dirt))
-                        if *age == POTATO_MATURE_AGE =>
-                    {
-                        true
-                    }
-                    (Tile::Dirt, None) if has_seeds => true,
-                    (_, _) => false,
-                };
-                if replace {
-                    most_significant = Some((x, y, tile));
+                let rank = rank(x, y, &self.tiles[x][y]);
+                if rank > highest {
+                    most_significant = Some((x, y));
+                    highest = rank;
                 }
             }
         }
@@ -290,16 +454,66 @@ impl Farm {
         most_significant
     }
 
-    fn execute(&mut self, x: usize, y: usize, state: &mut State) {
+    /// Do the `<Enter>` action on a tile. Its `energy_cost` is already paid, but harvesting more
+    /// plants with the hoe takes more out of `energy`.
+    fn execute(&mut self, x: usize, y: usize, state: &mut State, energy: &mut Stat) {
+        match self.tiles[x][y].clone() {
+            Tile::Potato {
+                age: POTATO_MATURE_AGE,
+                ..
+            } => {
+                self.harvest(x, y, state);
+                // a hoe lets the farmer harvest all the ripe plants around them in one go, as far as
+                // their energy goes
+                if state.inventory.count(Item::Hoe) > 0 {
+                    for (x, y) in neighbours(x, y, self.width(), self.height()) {
+                        if let Tile::Potato {
+                            age: POTATO_MATURE_AGE,
+                            ..
+                        } = self.tiles[x][y]
+                        {
+                            if !energy.try_sub(HARVEST_ENERGY) {
+                                break;
+                            }
+                            self.harvest(x, y, state);
+                        }
+                    }
+                }
+            }
+            Tile::Dirt if state.inventory.count(Item::Seeds) > 0 => {
+                if state.inventory.try_remove(Item::Seeds, 1) {
+                    self.tiles[x][y] = Tile::Potato {
+                        age: 0,
+                        blight: None,
+                    };
+                }
+            }
+            Tile::Overgrown => {
+                self.tiles[x][y] = Tile::Clearing {
+                    days_left: CLEARING_DAYS,
+                };
+            }
+            tile => {
+                eprintln!("Tile {:?} ({}/{}) is not actionable", tile, x, y)
+            }
+        }
+    }
+
+    fn harvest(&mut self, x: usize, y: usize, state: &mut State) {
         match self.tiles[x][y].clone() {
             Tile::Potato {
                 age: POTATO_MATURE_AGE,
                 blight,
             } => {
-                let potato_count = if state.rng.gen_bool(0.5) { 3 } else { 2 };
+                // tired soil grows smaller potatoes
+                let potato_count = match self.fertility[x][y] {
+                    0..=2 => state.rng.gen_range(0..=1),
+                    3..=5 => state.rng.gen_range(1..=2),
+                    _ => state.rng.gen_range(2..=3),
+                };
                 let seed_count = if state.rng.gen_bool(0.5) { 2 } else { 1 };
                 state.inventory.add(
-                    if blight {
+                    if blight.is_some() {
                         Item::RawPotatoBlight
                     } else {
                         Item::RawPotato
@@ -307,72 +521,719 @@ impl Farm {
                     potato_count,
                 );
                 state.inventory.add(Item::Seeds, seed_count);
+                state.inventory.add(Item::CropWaste, 1);
 
                 self.tiles[x][y] = Tile::Dirt;
-            }
-            Tile::Dirt if state.inventory.count(Item::Seeds) > 0 => {
-                if state.inventory.try_remove(Item::Seeds, 1) {
-                    self.tiles[x][y] = Tile::Potato {
-                        age: 0,
-                        blight: false,
-                    };
-                }
+                self.fertility[x][y] = self.fertility[x][y].saturating_sub(HARVEST_DEPLETION);
             }
             tile => {
-                eprintln!("Tile {:?} ({}/{}) is not actionable", tile, x, y)
+                eprintln!("Tile {:?} ({}/{}) is not ready to harvest", tile, x, y)
+            }
+        }
+    }
+
+    pub fn fertilize(&mut self, x: usize, y: usize, amount: u8) {
+        self.fertility[x][y] = (self.fertility[x][y] + amount).min(MAX_FERTILITY);
+    }
+
+    /// Take a close look at a plant. This reveals blight before it starts showing symptoms.
+    fn inspect(&mut self, x: usize, y: usize) -> (&'static str, Color) {
+        match &mut self.tiles[x][y] {
+            Tile::Potato {
+                blight: Some(blight),
+                ..
+            } => {
+                blight.spotted = true;
+                ("This plant has blight!", RED)
+            }
+            Tile::Potato { .. } => ("This plant looks healthy", WHITE),
+            Tile::Dirt => ("Just dirt", WHITE),
+            Tile::Overgrown => ("Weeds and brambles", WHITE),
+            Tile::Clearing { .. } => ("Almost cleared", WHITE),
+            Tile::Structure(structure) => (structure.item().name(), WHITE),
+        }
+    }
+
+    /// Destroy up to `count` random potato plants
+    pub fn trample(&mut self, rng: &mut impl Rng, count: usize) {
+        let mut plants = Vec::new();
+        self.for_each(|x, y, tile| {
+            if let Tile::Potato { .. } = tile {
+                plants.push((x, y));
             }
+        });
+        for _ in 0..count.min(plants.len()) {
+            let (x, y) = plants.swap_remove(rng.gen_range(0..plants.len()));
+            self.tiles[x][y] = Tile::Dirt;
         }
     }
 
+    pub fn count_plants(&self) -> usize {
+        self.tiles
+            .iter()
+            .flatten()
+            .filter(|tile| matches!(tile, Tile::Potato { .. }))
+            .count()
+    }
+
     pub fn for_each(&mut self, mut cb: impl FnMut(usize, usize, &mut Tile)) {
-        for x in 0..SIZE {
-            for y in 0..SIZE {
+        for x in 0..self.width() {
+            for y in 0..self.height() {
                 cb(x, y, &mut self.tiles[x][y]);
             }
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// The tiles the farmer can work on. They differ when there's something else to do right next to a
+/// plant.
+struct Hover {
+    /// The closest plant, which inspecting, uprooting and watering are about
+    plant: Option<(usize, usize)>,
+    /// The tile for the `<Enter>` action
+    target: Option<(usize, usize)>,
+}
+
+/// A day of work on the farm, until the farmer leaves or ends the day
+pub struct FarmDay {
+    px: f32,
+    py: f32,
+    facing: (i32, i32),
+    start_raw_potatoes: usize,
+    energy: Stat,
+    walked: f32,
+    message: Option<(String, Color, f64)>,
+    effects: Effects,
+}
+
+impl FarmDay {
+    pub fn new(state: &State) -> Self {
+        Self {
+            px: 150.,
+            py: 50.,
+            facing: (0, 1),
+            start_raw_potatoes: raw_potatoes(state),
+            energy: Stat::new(state.max_energy()),
+            walked: 0.,
+            message: None,
+            effects: Effects::default(),
+        }
+    }
+
+    /// Show a message above the farmer
+    fn say(&mut self, text: impl Into<String>, color: Color) {
+        self.message = Some((text.into(), color, get_time()));
+    }
+
+    fn too_tired(&mut self) {
+        self.say("I'm too tired for that", ORANGE);
+    }
+
+    /// Whether the farmer stands at the farmhouse door, where the day can be ended
+    fn at_door(&self) -> bool {
+        self.py < 150. && self.px < 64.
+    }
+
+    /// Follow the farmer once the farm no longer fits on the screen
+    fn camera(&self, farm: &Farm) -> (f32, f32) {
+        let (world_width, world_height) = farm.world_size();
+        (
+            (self.px - screen_width() / 2.).clamp(0., world_width - screen_width()),
+            (self.py - screen_height() / 2.).clamp(0., world_height - screen_height()),
+        )
+    }
+
+    /// Walk around and work the field for a frame
+    fn work(&mut self, farm: &mut Farm, state: &mut State) -> Transition {
+        let speed = if state.has_a_cold || self.energy.current == 0 {
+            PLAYER_SICK_SPEED
+        } else {
+            PLAYER_SPEED
+        };
+        let (world_width, world_height) = farm.world_size();
+
+        let mut dx = 0;
+        let mut dy = 0;
+
+        if is_key_down(KeyCode::D) || is_key_down(KeyCode::Right) {
+            dx += 1;
+        }
+        if is_key_down(KeyCode::A) || is_key_down(KeyCode::Left) {
+            dx -= 1;
+        }
+        if is_key_down(KeyCode::S) || is_key_down(KeyCode::Down) {
+            dy += 1;
+        }
+        if is_key_down(KeyCode::W) || is_key_down(KeyCode::Up) {
+            dy -= 1;
+        }
+
+        if dx != 0 || dy != 0 {
+            self.facing = (dx, dy);
+            self.px += (dx as f32) * speed;
+            self.py += (dy as f32) * speed;
+
+            let min_x = if self.py < 170. { 10. } else { 0. } + 16.;
+            let min_y = if self.px < 70. { 10. } else { 0. } + 32.;
+            self.px = self.px.max(min_x).min(world_width - 16.);
+            self.py = self.py.max(min_y).min(world_height - 32.);
+
+            self.walked += speed;
+            if self.walked >= WALK_PER_ENERGY {
+                self.walked -= WALK_PER_ENERGY;
+                self.energy.subn(1);
+            }
+        }
+
+        if is_key_pressed(KeyCode::Escape) {
+            return Transition::push(PauseMenu::default());
+        }
+        if self.at_door() {
+            if is_key_pressed(KeyCode::Enter) {
+                return Transition::Pop;
+            }
+        } else if is_key_pressed(KeyCode::Q) {
+            return Transition::Pop;
+        }
+
+        let (px, py) = (self.px, self.py);
+        if farm.compost.is_near((px, py)) {
+            if farm.compost.action_name(&state.inventory).is_some()
+                && is_key_pressed(KeyCode::Enter)
+            {
+                if self.energy.try_sub(COMPOST_ENERGY) {
+                    let fertilizer = state.inventory.count(Item::Fertilizer);
+                    farm.compost.execute(&mut state.inventory);
+                    let gained = state.inventory.count(Item::Fertilizer) - fertilizer;
+                    if gained > 0 {
+                        self.effects
+                            .text(format!("+{} fertilizer", gained), px, py, GREEN);
+                    } else {
+                        self.effects.burst(px, py, DARKBROWN, 8);
+                    }
+                } else {
+                    self.too_tired();
+                }
+            }
+            return Transition::Stay;
+        }
+
+        let Hover { plant, target } = farm.hover((px, py), self.facing, state);
+        if let Some((x, y)) = plant {
+            let (center_x, center_y) = tile_center(x, y);
+            if is_key_pressed(KeyCode::I) {
+                let (text, color) = farm.inspect(x, y);
+                self.say(text, color);
+            } else if is_key_pressed(KeyCode::U) && !self.energy.try_sub(UPROOT_ENERGY) {
+                self.too_tired();
+            } else if is_key_pressed(KeyCode::U) {
+                farm.tiles[x][y] = Tile::Dirt;
+                state.inventory.add(Item::CropWaste, 1);
+                self.effects.burst(center_x, center_y, BROWN, 12);
+                self.say("Uprooted the plant", WHITE);
+            }
+            if !farm.watered[x][y] && state.inventory.count(Item::WateringCan) > 0 {
+                if is_key_pressed(KeyCode::E) && !self.energy.try_sub(WATER_ENERGY) {
+                    self.too_tired();
+                } else if is_key_pressed(KeyCode::E) {
+                    farm.watered[x][y] = true;
+                    self.effects.burst(center_x, center_y, SKYBLUE, 10);
+                    self.say("Watered the plant", SKYBLUE);
+                }
+            }
+        }
+
+        if let Some((x, y)) = target {
+            let tile = &farm.tiles[x][y];
+            let is_dirt = matches!(tile, Tile::Dirt);
+            let pest = farm.pest_at(x, y);
+            let action_name = pest
+                .map(|pest| pest.action_name())
+                .or_else(|| tile.action_name(&state.inventory));
+            let action_cost = match pest {
+                Some(_) => PEST_ENERGY,
+                None => tile.energy_cost(&state.inventory),
+            };
+            let (center_x, center_y) = tile_center(x, y);
+            if state.inventory.count(Item::Fertilizer) > 0 {
+                if is_key_pressed(KeyCode::F) && !self.energy.try_sub(FERTILIZE_ENERGY) {
+                    self.too_tired();
+                } else if is_key_pressed(KeyCode::F)
+                    && state.inventory.try_remove(Item::Fertilizer, 1)
+                {
+                    farm.fertilize(x, y, FERTILIZER_BOOST);
+                    self.effects.burst(center_x, center_y, DARKBROWN, 10);
+                    self.say("Spread some fertilizer", WHITE);
+                }
+            }
+            if let (true, Some(structure)) = (is_dirt, held_structure(state)) {
+                if is_key_pressed(KeyCode::B) && !self.energy.try_sub(BUILD_ENERGY) {
+                    self.too_tired();
+                } else if is_key_pressed(KeyCode::B)
+                    && state.inventory.try_remove(structure.item(), 1)
+                {
+                    farm.tiles[x][y] = Tile::Structure(structure);
+                    self.effects.burst(center_x, center_y, GRAY, 16);
+                    self.say(
+                        format!("Built a {}", structure.item().name().to_lowercase()),
+                        WHITE,
+                    );
+                }
+            }
+            if action_name.is_some() {
+                if is_key_pressed(KeyCode::Enter) && !self.energy.try_sub(action_cost) {
+                    self.too_tired();
+                } else if is_key_pressed(KeyCode::Enter) {
+                    if pest.is_some() {
+                        farm.pests.retain(|pest| (pest.x, pest.y) != (x, y));
+                        self.effects.text("Shoo!", center_x, center_y, WHITE);
+                    } else {
+                        let potatoes = raw_potatoes(state);
+                        farm.execute(x, y, state, &mut self.energy);
+                        let gained = raw_potatoes(state) - potatoes;
+                        if gained > 0 {
+                            self.effects.text(
+                                format!("+{} potatoes", gained),
+                                center_x,
+                                center_y,
+                                YELLOW,
+                            );
+                        }
+                        self.effects.burst(center_x, center_y, BROWN, 12);
+                    }
+                }
+            }
+        }
+        Transition::Stay
+    }
+
+    fn draw_farm(&mut self, farm: &Farm, state: &State, assets: &Assets) {
+        clear_background(DARKGREEN);
+        let (cam_x, cam_y) = self.camera(farm);
+        for x in 0..farm.width() {
+            for y in 0..farm.height() {
+                let tile = &farm.tiles[x][y];
+                let tile_x = (FARM_START.0 + x) as f32 * TILE_PX - cam_x;
+                let tile_y = (FARM_START.1 + y) as f32 * TILE_PX - cam_y;
+                tile.draw_at(tile_x, tile_y, farm.fertility[x][y]);
+                if farm.watered[x][y] {
+                    draw_rectangle(
+                        tile_x,
+                        tile_y,
+                        TILE_PX,
+                        TILE_PX,
+                        Color::new(0.0, 0.1, 0.4, 0.25),
+                    );
+                }
+            }
+        }
+        for pest in &farm.pests {
+            pest.draw_at(
+                (FARM_START.0 + pest.x) as f32 * TILE_PX - cam_x,
+                (FARM_START.1 + pest.y) as f32 * TILE_PX - cam_y,
+                TILE_PX,
+            );
+        }
+        farm.draw_fence((cam_x, cam_y));
+        // the farmhouse stands left of the field, which only grows to the right and down
+        draw_texture(assets.farm, -cam_x, -cam_y, WHITE);
+        farm.compost.draw((cam_x, cam_y));
+        draw_texture(
+            assets.farmer_front,
+            self.px - cam_x - assets.farmer_front.width() / 2.0,
+            self.py - cam_y - assets.farmer_front.height() / 2.0,
+            WHITE,
+        );
+        self.effects.draw((cam_x, cam_y));
+
+        // the field gets darker as the day goes on
+        let day_progress = 1. - self.energy.current as f32 / self.energy.max as f32;
+        let hour = DAY_START_HOUR + (DAY_END_HOUR - DAY_START_HOUR) * day_progress;
+        let dusk = ((hour - 16.) / (DAY_END_HOUR - 16.)).clamp(0., 1.);
+        draw_rectangle(
+            0.,
+            0.,
+            screen_width(),
+            screen_height(),
+            Color::new(0.0, 0.0, 0.1, dusk * 0.5),
+        );
+        draw_text(
+            &format!("{:02}:{:02}", hour as u32, (hour.fract() * 60.) as u32),
+            screen_width() - 310.,
+            30.,
+            30.,
+            WHITE,
+        );
+        self.energy.draw_if_not_full(
+            "Energy",
+            screen_width() - 310.,
+            70.,
+            ExpectedChange::Decreasing,
+        );
+
+        let seed_count = state.inventory.count(Item::Seeds);
+        let raw_potato_count = raw_potatoes(state);
+
+        if state.has_a_cold {
+            draw_text("Sick", 10., screen_height() - 100., 40., SKYBLUE);
+        }
+        draw_text(
+            &format!("Seeds: {}", seed_count),
+            10.,
+            screen_height() - 70.,
+            24.,
+            WHITE,
+        );
+        draw_text(
+            &format!(
+                "Potatoes: {}{}",
+                if raw_potato_count > self.start_raw_potatoes {
+                    "+"
+                } else {
+                    ""
+                },
+                raw_potato_count - self.start_raw_potatoes
+            ),
+            10.,
+            screen_height() - 50.,
+            24.,
+            WHITE,
+        );
+
+        if self.at_door() {
+            draw_text_centered(
+                "<Enter> end day",
+                screen_width() / 2.0,
+                screen_height() - 10.,
+                40.,
+                WHITE,
+            );
+        } else {
+            draw_text("<Q> leave", 10., screen_height() - 10., 30., WHITE);
+        }
+
+        if let Some((text, color, shown_at)) = &self.message {
+            if get_time() - shown_at < MESSAGE_DURATION {
+                draw_text_centered(text, self.px - cam_x, self.py - cam_y - 40., 24., *color);
+            } else {
+                self.message = None;
+            }
+        }
+
+        if farm.compost.is_near((self.px, self.py)) {
+            if let Some(action_name) = farm.compost.action_name(&state.inventory) {
+                draw_text_centered(
+                    &format!("<Enter> {}", action_name),
+                    screen_width() / 2.0,
+                    screen_height() - 10.,
+                    40.,
+                    WHITE,
+                );
+            }
+            return;
+        }
+
+        let Hover { plant, target } = farm.hover((self.px, self.py), self.facing, state);
+        for (x, y) in plant.into_iter().chain(target) {
+            let tile = &farm.tiles[x][y];
+            let pest = farm.pest_at(x, y);
+            if matches!(tile, Tile::Potato { .. })
+                || pest.is_some()
+                || tile.action_name(&state.inventory).is_some()
+            {
+                draw_rectangle_lines(
+                    (x + FARM_START.0) as f32 * TILE_PX - cam_x,
+                    (y + FARM_START.1) as f32 * TILE_PX - cam_y,
+                    TILE_PX,
+                    TILE_PX,
+                    2.0,
+                    RED,
+                );
+            }
+        }
+        if let Some((x, y)) = target.or(plant) {
+            draw_text_centered(
+                soil_name(farm.fertility[x][y]),
+                screen_width() / 2.0,
+                screen_height() - 75.,
+                24.,
+                WHITE,
+            );
+        }
+
+        if let Some((x, y)) = plant {
+            draw_text_centered(
+                "<I> inspect  <U> uproot",
+                screen_width() / 2.0,
+                screen_height() - 50.,
+                24.,
+                WHITE,
+            );
+            if !farm.watered[x][y] && state.inventory.count(Item::WateringCan) > 0 {
+                draw_text(
+                    "<E> water",
+                    screen_width() - 160.,
+                    screen_height() - 75.,
+                    24.,
+                    WHITE,
+                );
+            }
+        }
+
+        if let Some((x, y)) = target {
+            let tile = &farm.tiles[x][y];
+            let action_name = farm
+                .pest_at(x, y)
+                .map(|pest| pest.action_name())
+                .or_else(|| tile.action_name(&state.inventory));
+            if state.inventory.count(Item::Fertilizer) > 0 {
+                draw_text(
+                    "<F> fertilize",
+                    screen_width() - 160.,
+                    screen_height() - 50.,
+                    24.,
+                    WHITE,
+                );
+            }
+            if let (Tile::Dirt, Some(structure)) = (tile, held_structure(state)) {
+                draw_text(
+                    &format!("<B> build {}", structure.item().name().to_lowercase()),
+                    screen_width() - 220.,
+                    screen_height() - 100.,
+                    24.,
+                    WHITE,
+                );
+            }
+            if let Some(action_name) = action_name {
+                draw_text_centered(
+                    &format!("<Enter> {}", action_name),
+                    screen_width() / 2.0,
+                    screen_height() - 10.,
+                    40.,
+                    WHITE,
+                );
+            }
+        }
+    }
+}
+
+impl Scene for FarmDay {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        let state = ctx.state_mut();
+        let Some(mut farm) = state.farm.take() else {
+            return Transition::Pop;
+        };
+        let transition = self.work(&mut farm, state);
+        state.farm = Some(farm);
+        transition
+    }
+
+    fn draw(&mut self, ctx: &Ctx) {
+        let state = ctx.state();
+        if let Some(farm) = &state.farm {
+            self.draw_farm(farm, state, ctx.assets);
+        }
+    }
+}
+
+/// Raw potatoes in the inventory, with or without blight
+fn raw_potatoes(state: &State) -> usize {
+    state.inventory.count(Item::RawPotato) + state.inventory.count(Item::RawPotatoBlight)
+}
+
+/// The first structure in the inventory, which is the one the farmer would build
+fn held_structure(state: &State) -> Option<Structure> {
+    state
+        .inventory
+        .items()
+        .find_map(|(item, _)| Structure::from_item(item))
+}
+
+/// The middle of a tile of the field, in the coordinates of the farm
+fn tile_center(x: usize, y: usize) -> (f32, f32) {
+    (
+        (x + FARM_START.0) as f32 * TILE_PX + TILE_PX / 2.,
+        (y + FARM_START.1) as f32 * TILE_PX + TILE_PX / 2.,
+    )
+}
+
+#[derive(Debug, Clone, Default)]
 pub enum Tile {
+    #[default]
     Dirt,
-    Potato { age: u8, blight: bool },
+    Potato {
+        age: u8,
+        blight: Option<Blight>,
+    },
+    /// Wild land at the edge of the farm
+    Overgrown,
+    /// Overgrown land the farmer is clearing, turns into dirt when done
+    Clearing {
+        days_left: u8,
+    },
+    Structure(Structure),
 }
 
-impl Default for Tile {
-    fn default() -> Self {
-        Self::Dirt
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blight {
+    /// Days since the plant got infected
+    pub days: u8,
+    /// Whether the farmer found this infection by inspecting the plant
+    pub spotted: bool,
+}
+
+impl Blight {
+    pub fn is_visible(&self) -> bool {
+        self.spotted || self.days >= BLIGHT_INCUBATION
     }
 }
 
 impl Tile {
-    pub fn draw_at(&self, x: f32, y: f32) {
+    pub fn draw_at(&self, x: f32, y: f32, fertility: u8) {
+        // depleted soil turns pale and sandy
+        const DEPLETED: Color = Color::new(0.76, 0.65, 0.45, 1.0);
+        let t = fertility as f32 / MAX_FERTILITY as f32;
+        let soil = Color::new(
+            DEPLETED.r + (BROWN.r - DEPLETED.r) * t,
+            DEPLETED.g + (BROWN.g - DEPLETED.g) * t,
+            DEPLETED.b + (BROWN.b - DEPLETED.b) * t,
+            1.0,
+        );
         match self {
-            Self::Dirt => draw_rectangle(x, y, TILE_PX, TILE_PX, BROWN),
-            Self::Potato { age, .. } => {
-                draw_rectangle(x, y, TILE_PX, TILE_PX, BROWN);
-                let height = *age as f32 * 3.;
-                let color = if *age == POTATO_MATURE_AGE {
+            Self::Dirt => draw_rectangle(x, y, TILE_PX, TILE_PX, soil),
+            Self::Potato { age, blight } => {
+                draw_rectangle(x, y, TILE_PX, TILE_PX, soil);
+                let stage = GrowthStage::of(*age);
+                let sick = blight.map(|b| b.is_visible()).unwrap_or_default();
+                let color = if sick {
+                    Color::new(0.45, 0.42, 0.12, 1.0)
+                } else if *age == POTATO_MATURE_AGE {
                     Color::new(0.701, 0.890, 0.0, 1.0)
                 } else {
                     GREEN
                 };
-                for (dx, dy) in [(15., 20.), (45., 20.), (30., 30.)] {
-                    draw_rectangle(x + dx - 1., y + dy - 1., 5.0, 5.0, DARKBROWN);
-                    draw_rectangle(x + dx, y + dy - height, 3.0, height, color);
+                for (dx, dy) in [(15., 20.), (40., 20.), (28., 38.)] {
+                    stage.draw_at(x + dx, y + dy, color, sick);
+                }
+            }
+            Self::Overgrown => {
+                draw_rectangle(x, y, TILE_PX, TILE_PX, Color::new(0.2, 0.3, 0.1, 1.0));
+                for (dx, dy) in [(8., 40.), (20., 25.), (33., 45.), (42., 15.), (12., 12.)] {
+                    draw_triangle(
+                        vec2(x + dx - 4., y + dy),
+                        vec2(x + dx + 4., y + dy),
+                        vec2(x + dx, y + dy - 10.),
+                        DARKGREEN,
+                    );
                 }
             }
+            Self::Clearing { .. } => {
+                draw_rectangle(x, y, TILE_PX, TILE_PX, soil);
+                for (dx, dy) in [(12., 15.), (35., 22.), (20., 38.)] {
+                    draw_circle(x + dx, y + dy, 4., DARKBROWN);
+                }
+            }
+            Self::Structure(structure) => {
+                draw_rectangle(x, y, TILE_PX, TILE_PX, soil);
+                structure.draw_at(x, y, TILE_PX);
+            }
+        }
+    }
+
+    /// Energy the farmer spends on the action from `action_name`
+    pub fn energy_cost(&self, inventory: &Inventory) -> u32 {
+        match self {
+            Self::Potato {
+                age: POTATO_MATURE_AGE,
+                ..
+            } => HARVEST_ENERGY,
+            Self::Dirt if inventory.count(Item::Seeds) > 0 => PLANT_ENERGY,
+            Self::Overgrown => CLEAR_ENERGY,
+            _ => 0,
         }
     }
 
-    pub fn action_name(&self) -> Option<&str> {
+    /// What pressing enter on the tile would do, if anything
+    pub fn action_name(&self, inventory: &Inventory) -> Option<&'static str> {
         match self {
             Self::Potato {
                 age: POTATO_MATURE_AGE,
                 ..
             } => Some("harvest potato"),
-            Self::Dirt => Some("plant potato"),
+            Self::Dirt if inventory.count(Item::Seeds) > 0 => Some("plant potato"),
+            Self::Overgrown => Some("clear land"),
             _ => None,
         }
     }
 }
+
+/// What a potato plant looks like as it grows
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum GrowthStage {
+    Seed,
+    Sprout,
+    Leafy,
+    Flowering,
+}
+
+impl GrowthStage {
+    fn of(age: u8) -> Self {
+        match age {
+            0 => Self::Seed,
+            1..=2 => Self::Sprout,
+            POTATO_MATURE_AGE.. => Self::Flowering,
+            _ => Self::Leafy,
+        }
+    }
+
+    /// Draw a single plant growing out of the ground at `x`, `y`
+    fn draw_at(&self, x: f32, y: f32, color: Color, sick: bool) {
+        draw_circle(x, y, 4., DARKBROWN);
+        let (height, leaf) = match self {
+            Self::Seed => return,
+            Self::Sprout => (6., 2.),
+            Self::Leafy => (10., 4.),
+            Self::Flowering => (12., 5.),
+        };
+        draw_line(x, y, x, y - height, 2., color);
+        draw_circle(x - leaf, y - height + leaf, leaf, color);
+        draw_circle(x + leaf, y - height + leaf, leaf, color);
+        if *self != Self::Sprout {
+            draw_circle(x, y - height, leaf, color);
+        }
+        if *self == Self::Flowering && !sick {
+            draw_circle(x - 3., y - height - 3., 2., WHITE);
+            draw_circle(x + 3., y - height - 2., 2., WHITE);
+        }
+        if sick {
+            draw_circle(x - leaf, y - height + leaf, 1., BLACK);
+            draw_circle(x + 1., y - height, 1., BLACK);
+        }
+    }
+}
+
+/// All tiles that touch the given tile, including diagonals
+fn neighbours(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    let min_x = if x == 0 { x } else { x - 1 };
+    let max_x = if x + 1 < width { x + 1 } else { x };
+    let min_y = if y == 0 { y } else { y - 1 };
+    let max_y = if y + 1 < height { y + 1 } else { y };
+
+    (min_x..=max_x)
+        .flat_map(move |tile_x| (min_y..=max_y).map(move |tile_y| (tile_x, tile_y)))
+        .filter(move |&(tile_x, tile_y)| tile_x != x || tile_y != y)
+}
+
+fn soil_name(fertility: u8) -> &'static str {
+    match fertility {
+        0..=2 => "Depleted soil",
+        3..=5 => "Tired soil",
+        6..=8 => "Good soil",
+        _ => "Rich soil",
+    }
+}
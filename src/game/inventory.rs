@@ -1,4 +1,5 @@
-use std::cmp::Ordering;
+use ::rand::Rng;
+use std::{cmp::Ordering, str::FromStr};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Item {
@@ -7,17 +8,91 @@ pub enum Item {
     RawPotatoBlight,
     CookedPotato,
     CanOfBeans,
-    Gun,
+    Scrap,
+    Wood,
+    Ammo,
+    Barricade,
+    CropWaste,
+    Fertilizer,
+    Hoe,
+    WateringCan,
+    Scarecrow,
+    Fence,
+    SpoiledFood,
+    Greenhouse,
+    RootCellar,
+    Well,
 }
 
 impl Item {
-    pub fn name(&self) -> &str {
+    /// Every item, so they can be looked up by their `key`
+    pub const ALL: [Item; 19] = [
+        Item::Seeds,
+        Item::RawPotato,
+        Item::RawPotatoBlight,
+        Item::CookedPotato,
+        Item::CanOfBeans,
+        Item::Scrap,
+        Item::Wood,
+        Item::Ammo,
+        Item::Barricade,
+        Item::CropWaste,
+        Item::Fertilizer,
+        Item::Hoe,
+        Item::WateringCan,
+        Item::Scarecrow,
+        Item::Fence,
+        Item::SpoiledFood,
+        Item::Greenhouse,
+        Item::RootCellar,
+        Item::Well,
+    ];
+
+    pub fn name(&self) -> &'static str {
         match self {
             Self::Seeds => "Potato seeds",
             Self::RawPotato | Self::RawPotatoBlight => "Raw potato",
             Self::CookedPotato => "Cooked potato",
             Self::CanOfBeans => "Can of beans",
-            Self::Gun => "A gun",
+            Self::Scrap => "Scrap metal",
+            Self::Wood => "Wood",
+            Self::Ammo => "Bullets",
+            Self::Barricade => "Barricade",
+            Self::CropWaste => "Crop waste",
+            Self::Fertilizer => "Fertilizer",
+            Self::Hoe => "Hoe",
+            Self::WateringCan => "Watering can",
+            Self::Scarecrow => "Scarecrow",
+            Self::Fence => "Fence",
+            Self::SpoiledFood => "Spoiled food",
+            Self::Greenhouse => "Greenhouse",
+            Self::RootCellar => "Root cellar",
+            Self::Well => "Well",
+        }
+    }
+
+    /// The short name that `Item::from_str` parses
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Seeds => "seeds",
+            Self::RawPotato => "raw potato",
+            Self::RawPotatoBlight => "blighted potato",
+            Self::CookedPotato => "cooked potato",
+            Self::CanOfBeans => "beans",
+            Self::Scrap => "scrap",
+            Self::Wood => "wood",
+            Self::Ammo => "ammo",
+            Self::Barricade => "barricade",
+            Self::CropWaste => "crop waste",
+            Self::Fertilizer => "fertilizer",
+            Self::Hoe => "hoe",
+            Self::WateringCan => "watering can",
+            Self::Scarecrow => "scarecrow",
+            Self::Fence => "fence",
+            Self::SpoiledFood => "spoiled food",
+            Self::Greenhouse => "greenhouse",
+            Self::RootCellar => "root cellar",
+            Self::Well => "well",
         }
     }
 
@@ -25,9 +100,32 @@ impl Item {
         matches!(self, Self::RawPotato | Self::RawPotatoBlight)
     }
 
+    /// Tools change what the farmer can do on the farm, and only need to be owned once
+    pub fn is_tool(&self) -> bool {
+        matches!(self, Self::Hoe | Self::WateringCan)
+    }
+
     pub fn is_edible(&self) -> bool {
         matches!(self, Self::CookedPotato | Self::CanOfBeans)
     }
+
+    /// Food that goes bad if it's stored too long
+    pub fn is_perishable(&self) -> bool {
+        matches!(self, Self::RawPotato | Self::CookedPotato)
+    }
+}
+
+impl FromStr for Item {
+    type Err = String;
+
+    /// Parse the short item names used in data files, e.g. `people/*.md`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key = s.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|item| item.key() == key)
+            .ok_or_else(|| format!("Unknown item {:?}", s))
+    }
 }
 
 pub struct Inventory {
@@ -75,6 +173,11 @@ impl Inventory {
             })
     }
 
+    /// The items as they are stored, without combining the blighted and healthy potatoes
+    pub fn stacks(&self) -> impl Iterator<Item = (Item, usize)> + '_ {
+        self.items.iter().cloned()
+    }
+
     pub fn count(&self, item: Item) -> usize {
         self.items
             .iter()
@@ -97,7 +200,29 @@ impl Inventory {
                 self.items.push((Item::CookedPotato, potatoes));
             }
         }
+        // blighted potatoes aren't fit to eat, but they'll still do for the compost heap
+        let blight_potatoes = self.count(Item::RawPotatoBlight);
         self.remove_all(Item::RawPotatoBlight);
+        if blight_potatoes > 0 {
+            self.add(Item::CropWaste, blight_potatoes);
+        }
+    }
+
+    /// Every perishable item has a `chance` to go bad. Returns how many items spoiled.
+    pub fn spoil(&mut self, chance: f64, rng: &mut impl Rng) -> usize {
+        let mut spoiled = 0;
+        for (item, count) in self.items.iter_mut() {
+            if item.is_perishable() {
+                let lost = (0..*count).filter(|_| rng.gen_bool(chance)).count();
+                *count -= lost;
+                spoiled += lost;
+            }
+        }
+        self.items.retain(|(_, count)| *count > 0);
+        if spoiled > 0 {
+            self.add(Item::SpoiledFood, spoiled);
+        }
+        spoiled
     }
 
     pub fn has_edibles(&self) -> bool {
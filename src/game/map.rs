@@ -0,0 +1,208 @@
+use super::{scavenge_town, Activity, FarmDay, State};
+use crate::{
+    dialogue::{get_num_pressed, Dialogue, DialogueBuilder},
+    pause::PauseMenu,
+    scene::{Ctx, Scene, Transition},
+};
+use macroquad::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Location {
+    Bunker,
+    Barn,
+    Town,
+    GregsHouse,
+}
+
+/// Health regained by resting in the bunker for a day
+const REST_HEALTH: u32 = 5;
+/// Food used up while resting. Without it the farmer is too hungry to get better.
+const REST_FOOD: u32 = 10;
+
+/// What there is at a location
+pub struct Site {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+static BUNKER: Site = Site {
+    name: "Bunker",
+    description: "Rest for the day",
+};
+
+static BARN: Site = Site {
+    name: "Barn",
+    description: "Tend to the potato field",
+};
+
+static TOWN: Site = Site {
+    name: "Town",
+    description: "Scavenge for supplies",
+};
+
+static GREGS_HOUSE: Site = Site {
+    name: "Greg's house",
+    description: "Visit an old friend",
+};
+
+impl Location {
+    /// All locations, in the order they're listed on the map
+    pub const ALL: [Location; 4] = [
+        Location::Bunker,
+        Location::Barn,
+        Location::Town,
+        Location::GregsHouse,
+    ];
+
+    pub fn site(&self) -> &'static Site {
+        match self {
+            Self::Bunker => &BUNKER,
+            Self::Barn => &BARN,
+            Self::Town => &TOWN,
+            Self::GregsHouse => &GREGS_HOUSE,
+        }
+    }
+
+    /// What the farmer ends up doing when they spend the day at the location
+    pub fn visit(self, state: &mut State) -> Box<dyn Scene> {
+        let scene: Option<Box<dyn Scene>> = match self {
+            Self::Bunker => Some(Box::new(rest(self.site(), state))),
+            Self::Barn => state
+                .farm
+                .is_some()
+                .then(|| Box::new(FarmDay::new(state)) as Box<dyn Scene>),
+            Self::Town => Some(Box::new(scavenge_town(state))),
+            Self::GregsHouse => call_on("greg", state).map(|d| Box::new(d) as Box<dyn Scene>),
+        };
+        scene.unwrap_or_else(|| {
+            Box::new(Dialogue::new(|d| {
+                d.page(state.page);
+                d.text(format!(
+                    "I went to the {}, but there was nothing to do.",
+                    self.site().name
+                ));
+            }))
+        })
+    }
+}
+
+/// Stay in at `site` for the day, which heals the farmer if there's enough food
+fn rest(site: &Site, state: &mut State) -> Dialogue {
+    let fed = state.food.current >= REST_FOOD;
+    let dialogue = Dialogue::new(|d| {
+        d.page(state.page);
+        d.text(format!(
+            "I stayed in the {} all day.",
+            site.name.to_lowercase()
+        ));
+        d.text("Not much to do, but at least it's safe in here.");
+        if !fed {
+            d.color_text("Too hungry to get any better", ORANGE);
+        } else if !state.health.is_max() {
+            d.color_text("Regained some health, but ate into my food", DARKGREEN);
+        }
+    });
+    if fed && !state.health.is_max() {
+        state.food.subn(REST_FOOD);
+        state.health.add(REST_HEALTH);
+    }
+    dialogue
+}
+
+/// Drop by the character from `people/<id>.md` at their home
+fn call_on(id: &str, state: &mut State) -> Option<Dialogue> {
+    let page = state.page;
+    let npc = state.npcs.find_mut(id)?;
+    let missed = npc.has_missed_farmer(page);
+    let gift = npc.roll_gift(&mut state.rng).filter(|_| missed);
+    let dialogue = Dialogue::new(|d| {
+        d.page(page);
+        d.text(format!("I walked up the hill to {}'s house.", npc.name()));
+        if missed {
+            d.text("He made us some coffee. Real coffee!");
+        } else {
+            d.text("We only just saw each other. He had work to do, so I didn't stay.");
+        }
+        if let Some((item, count)) = gift {
+            d.text("");
+            d.text("He had some things to spare.");
+            d.color_text(format!("Got {} x {}", count, item.name()), YELLOW);
+        }
+    });
+    if missed {
+        npc.change_relationship(5);
+    }
+    npc.meet(page);
+    if let Some((item, count)) = gift {
+        state.inventory.add(item, count);
+    }
+    Some(dialogue)
+}
+
+pub struct WorldMap {
+    unlocked: Vec<Location>,
+}
+
+impl Default for WorldMap {
+    fn default() -> Self {
+        Self {
+            unlocked: vec![Location::Bunker],
+        }
+    }
+}
+
+impl WorldMap {
+    pub fn is_unlocked(&self, location: Location) -> bool {
+        self.unlocked.contains(&location)
+    }
+
+    pub fn unlock(&mut self, location: Location) {
+        if !self.is_unlocked(location) {
+            self.unlocked.push(location);
+        }
+    }
+}
+
+/// Picking where to spend the day
+pub struct MapScreen;
+
+impl Scene for MapScreen {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        let state = ctx.state_mut();
+        let picked = get_num_pressed()
+            .and_then(|key| Location::ALL.get(key.checked_sub(1)?).copied())
+            .filter(|&location| state.map.is_unlocked(location));
+        if let Some(location) = picked {
+            return Transition::replace(Activity::new(location.visit(state)));
+        }
+
+        if is_key_pressed(KeyCode::M) {
+            Transition::Pop
+        } else if is_key_pressed(KeyCode::Escape) {
+            Transition::push(PauseMenu::default())
+        } else {
+            Transition::Stay
+        }
+    }
+
+    fn draw(&mut self, ctx: &Ctx) {
+        let map = &ctx.state().map;
+        let x = 50.;
+        let mut y = 50.;
+        draw_text("Map", x, y, 40., WHITE);
+        y += 80.;
+
+        for (index, location) in Location::ALL.into_iter().enumerate() {
+            if map.is_unlocked(location) {
+                let site = location.site();
+                draw_text(&format!("<{}> {}", index + 1, site.name), x, y, 30., WHITE);
+                draw_text(site.description, x + 30., y + 26., 24., GRAY);
+            } else {
+                draw_text("???", x, y, 30., DARKGRAY);
+            }
+            y += 70.;
+        }
+
+        draw_text("<M> back", 50., screen_height() - 50., 24., WHITE);
+    }
+}
@@ -1,22 +1,53 @@
 mod cat;
+mod compost;
+mod crafting;
+mod day;
 mod event;
 mod events;
 mod farm;
 mod inventory;
+mod map;
+mod npc;
+mod pest;
+mod quest;
+mod raid;
+mod save;
+mod scavenge;
+mod season;
+mod structure;
+mod weapon;
 
 pub use cat::*;
+pub use compost::*;
+pub use crafting::*;
+pub use day::*;
 pub use event::*;
 pub use events::*;
 pub use farm::*;
 pub use inventory::*;
+pub use map::*;
+pub use npc::*;
+pub use pest::*;
+pub use quest::*;
+pub use raid::*;
+pub use scavenge::*;
+pub use season::*;
+pub use structure::*;
+pub use weapon::*;
 
-use crate::{
-    assets::Assets,
-    dialogue::{Dialogue, DialogueBuilder},
-};
+use crate::dialogue::{Dialogue, DialogueBuilder, Journal};
 use ::rand::{thread_rng, Rng, RngCore};
 use macroquad::prelude::*;
 
+/// Above this amount of radiation the farmer starts losing health every day
+const RADIATION_SICKNESS: u32 = 30;
+const RADIATION_DECAY: u32 = 2;
+/// Energy the farmer has for a day of work on the farm when healthy and well fed
+const BASE_ENERGY: u32 = 100;
+/// Chance for every stored potato to go bad overnight
+const SPOIL_CHANCE: f64 = 0.04;
+const CELLAR_SPOIL_CHANCE: f64 = 0.005;
+
 pub struct State {
     pub rng: Box<dyn RngCore>,
     pub inventory: Inventory,
@@ -27,12 +58,22 @@ pub struct State {
     pub food: Stat,
     pub cat: CatState,
     pub has_a_cold: bool,
+    /// Too sick to do anything today, it's gone by the next morning
+    pub has_a_headache: bool,
     pub last_cook_had_blight: bool,
     pub farm: Option<Farm>,
+    pub map: WorldMap,
+    pub npcs: Npcs,
+    pub quests: Vec<Quest>,
+    pub raiders: Raiders,
+    pub gun: Option<Gun>,
+    pub radiation: u32,
+    /// Every diary page read so far
+    pub journal: Journal,
 }
 
 impl State {
-    pub fn new(start_page: u32) -> Self {
+    pub fn new(start_page: u32, people: &[Profile]) -> Self {
         Self {
             rng: Box::new(thread_rng()),
             start_page,
@@ -43,8 +84,16 @@ impl State {
             food: Stat::new(100),
             cat: CatState::NotVisited,
             has_a_cold: false,
+            has_a_headache: false,
             last_cook_had_blight: false,
             farm: None,
+            map: WorldMap::default(),
+            npcs: Npcs::new(people),
+            quests: Vec::new(),
+            raiders: Raiders::default(),
+            gun: None,
+            radiation: 0,
+            journal: Journal::default(),
         }
     }
     pub fn day_delta(&self) -> u32 {
@@ -53,6 +102,7 @@ impl State {
 
     pub fn end_of_day(&mut self) {
         self.page += 1;
+        self.has_a_headache = false;
         let food_count = self.rng.gen_range(5..20);
         if self.inventory.remove_edible() {
             self.food.add(food_count);
@@ -60,94 +110,53 @@ impl State {
             self.food.subn(food_count);
         }
 
+        let has_cellar = self
+            .farm
+            .as_ref()
+            .map(|farm| farm.has_structure(Structure::RootCellar))
+            .unwrap_or_default();
+        let spoil_chance = if has_cellar {
+            CELLAR_SPOIL_CHANCE
+        } else {
+            SPOIL_CHANCE
+        };
+        self.inventory.spoil(spoil_chance, &mut self.rng);
+
         if let Some(farm) = &mut self.farm {
-            farm.end_of_day(&mut self.rng);
+            let has_cat = self.cat.get().is_some();
+            farm.end_of_day(Season::of(self.page), has_cat, &mut self.rng);
         }
-    }
-
-    fn can_farm(&self) -> bool {
-        self.farm.is_some()
-    }
-
-    pub async fn draw(&mut self, last_event: Event, _assets: &Assets) -> DayAction {
-        next_frame().await;
-        loop {
-            clear_background(BLACK);
-
-            let x = 50.;
-            let mut y = 50.;
-            draw_text(&format!("Day {}", self.page), x, y, 40., WHITE);
-            y += 80.;
-
-            y += self
-                .health
-                .draw_if_not_full("Health", x, y, ExpectedChange::Unknown);
-            y += self.food.draw_if_not_full(
-                "Food",
-                x,
-                y,
-                if self.inventory.has_edibles() {
-                    ExpectedChange::Increasing
-                } else {
-                    ExpectedChange::Decreasing
-                },
-            );
-
-            if let Some(_cat) = self.cat.get() {
-                draw_text("Cat is happy", x, y, 24., WHITE);
-                y += 30.;
-            }
-
-            if self.inventory.has_items() {
-                draw_text("Inventory", x, y, 30., WHITE);
-                y += 40.;
-
-                for (item, count) in self.inventory.items() {
-                    if count == 1 {
-                        draw_text(item.name(), x, y, 24., WHITE);
-                    } else {
-                        draw_text(&format!("{}: {}", item.name(), count), x, y, 24., WHITE);
-                    }
-                    y += 24.
-                }
-                y += 10.;
-            }
-            let _ = y;
-
-            draw_text("<Esc> exit", 50., screen_height() - 50., 24., WHITE);
-
-            if self.inventory.has_cookables() && last_event.can_execute_action() {
-                draw_text("<C> cook", 450., screen_height() - 50., 24., WHITE);
-                if is_key_pressed(KeyCode::C) {
-                    self.cook().await;
-
-                    return DayAction::Next;
-                }
-            }
-            if self.can_farm() && last_event.can_execute_action() {
-                draw_text("<Enter> tend farm", 200., screen_height() - 50., 24., WHITE);
-                if is_key_pressed(KeyCode::Enter) {
-                    return DayAction::Farm;
-                }
-            } else {
-                draw_text("<Enter> Next day", 200., screen_height() - 50., 24., WHITE);
-                if is_key_pressed(KeyCode::Enter) {
-                    return DayAction::Next;
-                }
-            }
 
-            if is_key_pressed(KeyCode::Escape) {
-                crate::quit_dialogue().await;
+        if self.radiation > RADIATION_SICKNESS {
+            let damage = (self.radiation - RADIATION_SICKNESS) / 10 + 1;
+            if !self.health.subn(damage) {
+                self.is_dead = true;
             }
+        }
+        self.radiation = self.radiation.saturating_sub(RADIATION_DECAY);
+    }
 
-            next_frame().await;
+    /// How much the farmer can get done on the farm today
+    pub fn max_energy(&self) -> u32 {
+        let mut energy = BASE_ENERGY;
+        if self.has_a_cold {
+            energy = energy * 6 / 10;
+        }
+        if self.food.current * 10 < self.food.max * 3 {
+            energy = energy * 7 / 10;
         }
+        energy
+    }
+
+    fn can_farm(&self) -> bool {
+        self.farm.is_some()
     }
 
-    async fn cook(&mut self) {
+    /// Spend the day cooking all the raw potatoes
+    fn cook(&mut self) -> Dialogue {
         let potatoes = self.inventory.count(Item::RawPotato);
         let blight_potatoes = self.inventory.count(Item::RawPotatoBlight);
-        Dialogue::show(|d| {
+        let dialogue = Dialogue::new(|d| {
             d.page(self.page);
             d.text("I decided to spend the day cooking");
             d.text("");
@@ -163,30 +172,26 @@ impl State {
             d.text("");
             if blight_potatoes > 0 {
                 if self.last_cook_had_blight {
-                    d.text("I found even more blight on my potatoes...");
+                    d.rich_text("I found even more <red>blight</red> on my potatoes...");
                 } else {
                     d.jiggle_color_text("THERE WAS BLIGHT ON MY POTATOES", RED);
                     d.text("This is terrible.");
-                    d.text("Blight is almost impossible to detect and spreads between plants.");
+                    d.text("Blight is hard to spot early and spreads between plants.");
                     d.text("My entire crop could be ruined.");
                     d.text("What will I do...");
                 }
                 d.text(format!("<Lost {} potatoes to blight>", blight_potatoes));
+                d.text("At least they'll do for the compost heap.");
             } else {
                 d.text("The house smelled amazing.");
             }
-        })
-        .await;
+        });
         self.last_cook_had_blight = blight_potatoes > 0;
         self.inventory.cook_all();
+        dialogue
     }
 }
 
-pub enum DayAction {
-    Farm,
-    Next,
-}
-
 #[derive(Debug)]
 pub struct Stat {
     pub current: u32,
@@ -212,6 +217,16 @@ impl Stat {
         }
     }
 
+    /// Subtract `count`, but only if there is enough left
+    pub fn try_sub(&mut self, count: u32) -> bool {
+        if self.current >= count {
+            self.current -= count;
+            true
+        } else {
+            false
+        }
+    }
+
     fn is_max(&self) -> bool {
         self.current == self.max
     }
@@ -0,0 +1,296 @@
+use super::{has_active_quest, Item, Quest, QuestGiver, State};
+use crate::{
+    assets::Assets,
+    dialogue::{DialogueBuilder, Prompt},
+    scene::{Scene, Transition},
+};
+use ::rand::Rng;
+use macroquad::prelude::{DARKGREEN, RED, YELLOW};
+
+/// Relationship an NPC needs before they start bringing gifts
+const GIFT_RELATIONSHIP: i32 = 30;
+const MAX_RELATIONSHIP: i32 = 100;
+/// Days before a character who never visits on their own is glad to see the farmer again
+const DEFAULT_VISIT_INTERVAL: u32 = 5;
+
+/// The static description of a character, loaded from `people/<id>.md`.
+///
+/// Free-form bullets at the top of the file are only for whoever writes the characters. The
+/// `Stats`, `Gifts` and `Requests` sections contain `key: value` bullets.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub relationship: i32,
+    /// How many days pass between visits. `None` means the character never visits on their own.
+    pub visit_interval: Option<u32>,
+    pub gifts: Vec<(Item, usize)>,
+    pub requests: Vec<(Item, usize)>,
+}
+
+impl Profile {
+    pub fn parse(id: &str, contents: &str) -> Self {
+        let mut profile = Profile {
+            id: id.to_owned(),
+            name: id.to_owned(),
+            ..Default::default()
+        };
+        let mut section = String::new();
+        for line in contents.lines().map(str::trim) {
+            if let Some(name) = line.strip_prefix("## ") {
+                section = name.trim().to_lowercase();
+            } else if let Some(name) = line.strip_prefix("# ") {
+                profile.name = name.trim().to_owned();
+            } else if let Some(bullet) = line.strip_prefix("- ") {
+                profile.parse_bullet(&section, bullet.trim());
+            }
+        }
+        profile
+    }
+
+    fn parse_bullet(&mut self, section: &str, bullet: &str) {
+        if section.is_empty() {
+            return;
+        }
+        let (key, value) = match bullet.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => {
+                eprintln!("{}: expected `key: value`, got {:?}", self.id, bullet);
+                return;
+            }
+        };
+        // values may have a unit behind them, e.g. `6 days`
+        let number = value.split_whitespace().next().unwrap_or_default();
+        match (section, key) {
+            ("stats", "relationship") => match number.parse() {
+                Ok(n) => self.relationship = n,
+                Err(e) => eprintln!("{}: invalid relationship {:?}: {}", self.id, value, e),
+            },
+            ("stats", "visits every") => match number.parse() {
+                Ok(n) => self.visit_interval = Some(n),
+                Err(e) => eprintln!("{}: invalid visit interval {:?}: {}", self.id, value, e),
+            },
+            ("gifts", item) | ("requests", item) => match (item.parse(), number.parse()) {
+                (Ok(item), Ok(count)) if section == "gifts" => self.gifts.push((item, count)),
+                (Ok(item), Ok(count)) => self.requests.push((item, count)),
+                (Err(e), _) => eprintln!("{}: {}", self.id, e),
+                (_, Err(e)) => eprintln!("{}: invalid count {:?}: {}", self.id, value, e),
+            },
+            _ => eprintln!("{}: unknown key {:?} in {:?}", self.id, key, section),
+        }
+    }
+}
+
+pub struct Npc {
+    pub profile: Profile,
+    pub relationship: i32,
+    /// The page the farmer last saw this character, if they have met at all
+    pub last_seen: Option<u32>,
+}
+
+impl Npc {
+    pub fn new(profile: Profile) -> Self {
+        Self {
+            relationship: profile.relationship,
+            profile,
+            last_seen: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.profile.name
+    }
+
+    pub fn meet(&mut self, page: u32) {
+        self.last_seen = Some(page);
+    }
+
+    pub fn change_relationship(&mut self, delta: i32) {
+        self.relationship = (self.relationship + delta).clamp(-MAX_RELATIONSHIP, MAX_RELATIONSHIP);
+    }
+
+    /// What they say when they show up at the door
+    pub fn greeting(&self) -> &str {
+        match self.relationship {
+            i32::MIN..=-1 => "I'm not staying long.",
+            0..=29 => "Hey. Just checking in on you.",
+            _ => "Good to see you're still kicking!",
+        }
+    }
+
+    pub fn mood(&self) -> &str {
+        match self.relationship {
+            i32::MIN..=-30 => "hates you",
+            -29..=-1 => "is annoyed with you",
+            0..=29 => "is an acquaintance",
+            30..=69 => "is a friend",
+            _ => "is a close friend",
+        }
+    }
+
+    fn is_due(&self, page: u32) -> bool {
+        match (self.last_seen, self.profile.visit_interval) {
+            (Some(last_seen), Some(interval)) => page >= last_seen + interval,
+            _ => false,
+        }
+    }
+
+    /// Whether it has been long enough since they last saw the farmer for a visit to their place to
+    /// be special. Dropping by every day wears out the welcome.
+    pub fn has_missed_farmer(&self, page: u32) -> bool {
+        let interval = self
+            .profile
+            .visit_interval
+            .unwrap_or(DEFAULT_VISIT_INTERVAL);
+        self.last_seen
+            .is_none_or(|last_seen| page >= last_seen + interval)
+    }
+
+    /// Pick a gift to bring, if the relationship is good enough
+    pub fn roll_gift(&self, rng: &mut impl Rng) -> Option<(Item, usize)> {
+        if self.relationship < GIFT_RELATIONSHIP || self.profile.gifts.is_empty() {
+            return None;
+        }
+        Some(self.profile.gifts[rng.gen_range(0..self.profile.gifts.len())])
+    }
+
+    fn roll_request(&self, rng: &mut impl Rng) -> Option<(Item, usize)> {
+        if self.profile.requests.is_empty() {
+            return None;
+        }
+        Some(self.profile.requests[rng.gen_range(0..self.profile.requests.len())])
+    }
+}
+
+#[derive(Default)]
+pub struct Npcs {
+    npcs: Vec<Npc>,
+}
+
+impl Npcs {
+    pub fn new(people: &[Profile]) -> Self {
+        Self {
+            npcs: people.iter().cloned().map(Npc::new).collect(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> &Npc {
+        &self.npcs[index]
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> &mut Npc {
+        &mut self.npcs[index]
+    }
+
+    pub fn has(&self, index: usize) -> bool {
+        index < self.npcs.len()
+    }
+
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut Npc> {
+        self.npcs.iter_mut().find(|npc| npc.profile.id == id)
+    }
+
+    /// All characters the farmer has met so far
+    pub fn met(&self) -> impl Iterator<Item = &Npc> + '_ {
+        self.npcs.iter().filter(|npc| npc.last_seen.is_some())
+    }
+
+    /// The first character that is scheduled to drop by on the given page
+    pub fn due_visitor(&self, page: u32) -> Option<usize> {
+        self.npcs.iter().position(|npc| npc.is_due(page))
+    }
+}
+
+/// A scheduled visit from a recurring character.
+///
+/// They might bring a gift and ask for something in return, either right away or as a quest to
+/// finish in the next few days. Helping them out improves the relationship, refusing makes it
+/// worse.
+pub fn npc_visit(state: &mut State, index: usize, assets: &Assets) -> impl Scene {
+    let quest = if has_active_quest(state, QuestGiver::Npc(index)) {
+        None
+    } else {
+        Quest::for_npc(state, index).filter(|_| state.rng.gen_bool(0.5))
+    };
+    let npc = state.npcs.get(index);
+    let gift = npc.roll_gift(&mut state.rng);
+    let request = npc
+        .roll_request(&mut state.rng)
+        .filter(|_| state.rng.gen_bool(0.5));
+    let can_help = request
+        .map(|(item, count)| state.inventory.count(item) >= count)
+        .unwrap_or_default();
+
+    let prompt = Prompt::new(|p| {
+        p.page(state.page);
+        p.text(format!("{} came by today.", npc.name()));
+        p.say(npc.name(), assets.portrait(&npc.profile.id), npc.greeting());
+        if let Some((item, count)) = gift {
+            p.text("They brought me something.");
+            p.color_text(format!("Got {} x {}", count, item.name()), YELLOW);
+        }
+        match request {
+            Some((item, count)) => {
+                p.text(format!(
+                    "They asked if I could spare {} x {}.",
+                    count,
+                    item.name()
+                ));
+                if can_help {
+                    p.add_numbered_option(0, "refuse", false)
+                        .text("I told them I needed it myself.")
+                        .color_text(format!("{} seemed disappointed", npc.name()), RED);
+                    p.add_option(format!("give {} x {}", count, item.name()), true)
+                        .text("I handed it over. They were very grateful.")
+                        .color_text(format!("{} appreciated that", npc.name()), DARKGREEN);
+                } else {
+                    p.text("I didn't have enough to give them anything.");
+                    p.skippable(false);
+                }
+            }
+            None => match &quest {
+                Some(quest) => {
+                    p.text(format!(
+                        "They asked me to {} within {} days.",
+                        quest.objective.description(),
+                        quest.days_left(state.page)
+                    ));
+                    p.add_numbered_option(0, "decline", false)
+                        .text("I told them I had enough on my plate already.");
+                    p.add_option("accept", true)
+                        .text("I promised I would.")
+                        .color_text(
+                            format!("New quest: {}", quest.objective.description()),
+                            YELLOW,
+                        );
+                }
+                None => {
+                    p.text("We sat down and talked for a while.");
+                    p.skippable(false);
+                }
+            },
+        }
+    });
+
+    prompt.then(move |accepted, ctx| {
+        let state = ctx.state_mut();
+        let page = state.page;
+        if let Some((item, count)) = gift {
+            state.inventory.add(item, count);
+        }
+        let npc = state.npcs.get_mut(index);
+        npc.meet(page);
+        match (request, accepted) {
+            (Some((item, count)), true) if can_help => {
+                state.inventory.remove(item, count);
+                npc.change_relationship(15);
+            }
+            (Some(_), _) if can_help => npc.change_relationship(-10),
+            (Some(_), _) => npc.change_relationship(-2),
+            (None, true) if quest.is_some() => state.quests.extend(quest),
+            (None, _) if quest.is_some() => npc.change_relationship(-5),
+            (None, _) => npc.change_relationship(5),
+        }
+        Transition::Pop
+    })
+}
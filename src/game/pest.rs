@@ -0,0 +1,91 @@
+use super::{Season, Tile};
+use macroquad::prelude::*;
+
+/// Crows only go for freshly planted seeds and young sprouts
+const CROW_MAX_AGE: u8 = 1;
+/// Beetles go for the leaves of plants that have grown a bit
+const BEETLE_MIN_AGE: u8 = 2;
+/// Growth a plant loses every day it has beetles on it
+const BEETLE_DAMAGE: u8 = 1;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PestKind {
+    Crow,
+    Beetle,
+}
+
+impl PestKind {
+    /// Whether this pest goes after the given tile
+    pub fn targets(&self, tile: &Tile) -> bool {
+        match (self, tile) {
+            (Self::Crow, Tile::Potato { age, .. }) => *age <= CROW_MAX_AGE,
+            (Self::Beetle, Tile::Potato { age, .. }) => *age >= BEETLE_MIN_AGE,
+            _ => false,
+        }
+    }
+
+    /// Chance for a new swarm to show up on the farm overnight
+    pub fn spawn_chance(&self, season: Season) -> f64 {
+        match (self, season) {
+            (Self::Crow, _) => 0.15,
+            (Self::Beetle, Season::Summer) => 0.2,
+            (Self::Beetle, Season::Winter) => 0.0,
+            (Self::Beetle, _) => 0.08,
+        }
+    }
+
+    pub fn action_name(&self) -> &'static str {
+        match self {
+            Self::Crow => "chase off crow",
+            Self::Beetle => "pick off beetles",
+        }
+    }
+}
+
+/// A pest sitting on a tile of the farm
+#[derive(Copy, Clone, Debug)]
+pub struct Pest {
+    pub kind: PestKind,
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Pest {
+    /// Do a day's worth of damage to the tile the pest is on
+    pub fn damage(&self, tile: &mut Tile) {
+        match self.kind {
+            // crows dig up the seed and eat it
+            PestKind::Crow => *tile = Tile::Dirt,
+            PestKind::Beetle => {
+                if let Tile::Potato { age, .. } = tile {
+                    if *age <= BEETLE_DAMAGE {
+                        *tile = Tile::Dirt;
+                    } else {
+                        *age -= BEETLE_DAMAGE;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn draw_at(&self, x: f32, y: f32, size: f32) {
+        match self.kind {
+            PestKind::Crow => {
+                let (cx, cy) = (x + size * 0.6, y + size * 0.6);
+                draw_circle(cx, cy, 8., BLACK);
+                draw_circle(cx - 7., cy - 6., 5., BLACK);
+                draw_triangle(
+                    vec2(cx - 11., cy - 8.),
+                    vec2(cx - 11., cy - 4.),
+                    vec2(cx - 17., cy - 6.),
+                    ORANGE,
+                );
+            }
+            PestKind::Beetle => {
+                for (dx, dy) in [(10., 35.), (25., 12.), (38., 30.), (18., 22.)] {
+                    draw_circle(x + dx, y + dy, 3., Color::new(0.2, 0.1, 0.3, 1.0));
+                }
+            }
+        }
+    }
+}
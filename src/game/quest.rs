@@ -0,0 +1,167 @@
+use super::{Item, State};
+use crate::dialogue::{Dialogue, DialogueBuilder};
+use ::rand::Rng;
+use macroquad::prelude::{DARKGREEN, RED, YELLOW};
+
+/// How many days a visitor gives the farmer to finish a quest
+const QUEST_DAYS: u32 = 5;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QuestGiver {
+    Trader,
+    /// A recurring character from `State::npcs`, by index
+    Npc(usize),
+}
+
+impl QuestGiver {
+    pub fn name<'a>(&self, state: &'a State) -> &'a str {
+        match self {
+            Self::Trader => "The trader",
+            Self::Npc(index) => state.npcs.get(*index).name(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Objective {
+    /// Hand over this many items when the giver comes back
+    Deliver(Item, usize),
+    /// Have this many potato plants growing on the farm
+    Plant(usize),
+}
+
+impl Objective {
+    pub fn description(&self) -> String {
+        match self {
+            Self::Deliver(item, count) => format!("bring {} x {}", count, item.name()),
+            Self::Plant(count) => format!("grow {} potato plants", count),
+        }
+    }
+
+    fn is_met(&self, state: &State) -> bool {
+        match self {
+            Self::Deliver(item, count) => state.inventory.count(*item) >= *count,
+            Self::Plant(count) => state
+                .farm
+                .as_ref()
+                .map(|farm| farm.count_plants() >= *count)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+pub struct Quest {
+    pub giver: QuestGiver,
+    pub objective: Objective,
+    /// The page on which the giver comes back to collect
+    pub deadline: u32,
+    pub reward: (Item, usize),
+}
+
+impl Quest {
+    pub fn for_trader(state: &State) -> Self {
+        Self {
+            giver: QuestGiver::Trader,
+            objective: Objective::Deliver(Item::CookedPotato, 20),
+            deadline: state.page + QUEST_DAYS,
+            reward: (Item::Seeds, 15),
+        }
+    }
+
+    /// Come up with a quest based on the character's profile
+    pub fn for_npc(state: &mut State, index: usize) -> Option<Self> {
+        let npc = state.npcs.get(index);
+        let reward = npc
+            .profile
+            .gifts
+            .first()
+            .map(|(item, count)| (*item, count * 3))
+            .unwrap_or((Item::Seeds, 10));
+        let objective = if state.farm.is_some() && state.rng.gen_bool(0.5) {
+            Objective::Plant(10)
+        } else {
+            let requests = &npc.profile.requests;
+            if requests.is_empty() {
+                return None;
+            }
+            let (item, count) = requests[state.rng.gen_range(0..requests.len())];
+            Objective::Deliver(item, count * 3)
+        };
+        Some(Self {
+            giver: QuestGiver::Npc(index),
+            objective,
+            deadline: state.page + QUEST_DAYS,
+            reward,
+        })
+    }
+
+    pub fn days_left(&self, page: u32) -> u32 {
+        self.deadline.saturating_sub(page)
+    }
+}
+
+/// Whether the given visitor is still waiting on the farmer for something
+pub fn has_active_quest(state: &State, giver: QuestGiver) -> bool {
+    state.quests.iter().any(|q| q.giver == giver)
+}
+
+/// The giver of a quest comes back to see if the farmer did what they asked
+pub fn resolve_quest(state: &mut State, index: usize) -> Dialogue {
+    let quest = state.quests.remove(index);
+    let met = quest.objective.is_met(state);
+    let (reward_item, reward_count) = quest.reward;
+    // failing the trader costs you; he takes half your seeds for his trouble
+    let penalty = state.inventory.count(Item::Seeds) / 2;
+
+    let dialogue = Dialogue::new(|d| {
+        let name = quest.giver.name(state);
+        d.page(state.page);
+        d.text(format!(
+            "{} came back today. I promised to {}.",
+            name,
+            quest.objective.description()
+        ));
+        if met {
+            d.text("And I did!");
+            d.color_text(
+                format!("Got {} x {}", reward_count, reward_item.name()),
+                YELLOW,
+            );
+            if let QuestGiver::Npc(_) = quest.giver {
+                d.color_text(format!("{} is very happy with me", name), DARKGREEN);
+            }
+        } else {
+            d.text("I didn't manage to do it...");
+            match quest.giver {
+                QuestGiver::Trader if penalty > 0 => {
+                    d.text("He was furious and took some of my seeds for his trouble.");
+                    d.color_text(format!("Lost {} seeds", penalty), RED);
+                }
+                QuestGiver::Trader => {
+                    d.text("He was furious, but there was nothing for him to take.");
+                }
+                QuestGiver::Npc(_) => {
+                    d.color_text(format!("{} is disappointed in me", name), RED);
+                }
+            }
+        }
+    });
+
+    if met {
+        if let Objective::Deliver(item, count) = quest.objective {
+            state.inventory.remove(item, count);
+        }
+        state.inventory.add(reward_item, reward_count);
+    }
+    match quest.giver {
+        QuestGiver::Trader if !met => state.inventory.remove(Item::Seeds, penalty),
+        QuestGiver::Trader => {}
+        QuestGiver::Npc(index) => {
+            let page = state.page;
+            let npc = state.npcs.get_mut(index);
+            npc.meet(page);
+            npc.change_relationship(if met { 20 } else { -20 });
+        }
+    }
+    dialogue
+}
@@ -0,0 +1,327 @@
+use super::{Item, Shot, State};
+use crate::{
+    dialogue::{DialogueBuilder, Prompt, PromptLine},
+    scene::{Scene, Transition},
+};
+use ::rand::Rng;
+use macroquad::prelude::{DARKGREEN, RED, YELLOW};
+
+const BASE_DEMAND: usize = 70;
+const BASE_COOLDOWN: u32 = 8;
+/// Raiders won't bother with a farm that has fewer cooked potatoes than this
+const BASE_INTEREST: usize = 50;
+const GUNSHOT_DAMAGE: u32 = 30;
+/// Seconds the farmer has to decide before the raiders lose their patience
+const DECISION_TIME: f32 = 10.;
+
+/// What the raiders remember about this farm
+#[derive(Default)]
+pub struct Raiders {
+    /// How many times the farmer handed over food
+    pub complied: u32,
+    /// How many times the farmer fought them off
+    pub resisted: u32,
+    pub last_raid: Option<u32>,
+}
+
+impl Raiders {
+    /// Every time the farmer complies the raiders get greedier
+    pub fn demand(&self, has_cat: bool) -> usize {
+        let demand = BASE_DEMAND + self.complied as usize * 20;
+        if has_cat {
+            demand + 30
+        } else {
+            demand
+        }
+    }
+
+    /// Raiders come back sooner to a farm that pays up, and stay away from one that fights back
+    fn cooldown(&self) -> u32 {
+        (BASE_COOLDOWN + self.resisted * 3).saturating_sub(self.complied * 2)
+    }
+
+    pub fn is_interested(&self, page: u32, cooked_potatoes: usize) -> bool {
+        let threshold = BASE_INTEREST.saturating_sub(self.complied as usize * 10);
+        let rested = match self.last_raid {
+            Some(last_raid) => page >= last_raid + self.cooldown(),
+            None => true,
+        };
+        rested && cooked_potatoes > threshold
+    }
+}
+
+#[derive(Default, Clone)]
+struct Outcome {
+    potatoes_lost: usize,
+    damage: u32,
+    trampled: usize,
+    barricades_lost: usize,
+    ammo_used: usize,
+    gun_wear: u32,
+    complied: bool,
+    resisted: bool,
+}
+
+impl Outcome {
+    fn describe<T>(&self, option: &mut PromptLine<T>, health: u32) {
+        if self.ammo_used > 0 {
+            option.color_text(format!("<Used {} bullets>", self.ammo_used), YELLOW);
+        }
+        if self.barricades_lost > 0 {
+            option.color_text("<Lost a barricade>", RED);
+        }
+        if self.potatoes_lost > 0 {
+            option.color_text(format!("<Lost {} potatoes>", self.potatoes_lost), RED);
+        }
+        if self.trampled > 0 {
+            option.color_text("They trampled some of my plants on the way out", RED);
+        }
+        if self.damage >= health {
+            option.color_text("They shoot you. You die.", RED);
+        } else if self.damage > 0 {
+            option.color_text("<Lost health>", RED);
+        }
+    }
+}
+
+/// Raiders show up demanding food.
+///
+/// How the farmer responds changes how greedy the raiders get and how soon they come back.
+pub fn raid(state: &mut State) -> impl Scene {
+    let has_cat = state.cat.get().is_some();
+    let requested = state.raiders.demand(has_cat);
+    let potato_count = state.inventory.count(Item::CookedPotato);
+    let barricades = state.inventory.count(Item::Barricade);
+    let ammo = state.inventory.count(Item::Ammo);
+    let health = state.health.current;
+    // every fence keeps a couple of plants safe
+    let fences = state.inventory.count(Item::Fence);
+    let trample = |count: usize| count.saturating_sub(fences * 2);
+    let shot = state
+        .gun
+        .as_ref()
+        .map(|gun| (gun.fire(ammo, &state.health, &mut state.rng), gun));
+    let rng = &mut state.rng;
+
+    let refuse = Outcome {
+        damage: GUNSHOT_DAMAGE,
+        trampled: trample(rng.gen_range(2..6)),
+        resisted: true,
+        ..Default::default()
+    };
+    let comply = Outcome {
+        potatoes_lost: requested,
+        complied: true,
+        ..Default::default()
+    };
+    let hidden = rng.gen_bool(0.5);
+    let hide = if hidden {
+        Outcome::default()
+    } else {
+        Outcome {
+            potatoes_lost: potato_count.min(requested * 3 / 2),
+            damage: 10,
+            ..Default::default()
+        }
+    };
+    let negotiate_chance = (0.3 + state.raiders.complied as f64 * 0.1).min(0.8);
+    let negotiated = rng.gen_bool(negotiate_chance);
+    let negotiate = if negotiated {
+        Outcome {
+            potatoes_lost: (requested / 2).min(potato_count),
+            complied: true,
+            ..Default::default()
+        }
+    } else {
+        Outcome {
+            potatoes_lost: requested.min(potato_count),
+            trampled: trample(rng.gen_range(1..3)),
+            complied: true,
+            ..Default::default()
+        }
+    };
+    let barricade = if rng.gen_bool((barricades as f64 * 0.3).min(0.9)) {
+        Outcome {
+            barricades_lost: usize::from(rng.gen_bool(0.3)),
+            resisted: true,
+            ..Default::default()
+        }
+    } else {
+        Outcome {
+            barricades_lost: 1,
+            damage: 15,
+            potatoes_lost: requested.min(potato_count),
+            ..Default::default()
+        }
+    };
+    let gun = match shot {
+        Some((shot @ Shot::Fired { hit: true, .. }, gun)) => Outcome {
+            ammo_used: shot.ammo_used(),
+            gun_wear: gun.wear(shot),
+            resisted: true,
+            ..Default::default()
+        },
+        Some((shot @ Shot::Fired { hit: false, .. }, gun)) => Outcome {
+            ammo_used: shot.ammo_used(),
+            gun_wear: gun.wear(shot),
+            damage: GUNSHOT_DAMAGE / 2,
+            potatoes_lost: requested.min(potato_count),
+            ..Default::default()
+        },
+        Some((Shot::Empty, _)) if rng.gen_bool(0.4) => Outcome {
+            resisted: true,
+            ..Default::default()
+        },
+        Some((shot, gun)) => Outcome {
+            gun_wear: gun.wear(shot),
+            damage: GUNSHOT_DAMAGE,
+            potatoes_lost: requested.min(potato_count),
+            ..Default::default()
+        },
+        None => Outcome::default(),
+    };
+    let gun_condition = state
+        .gun
+        .as_ref()
+        .map(|gun| gun.condition.current)
+        .unwrap_or_default();
+
+    let prompt = Prompt::new(|p| {
+        p.page(state.page);
+        p.text("Raiders came in last night demanding food.");
+        match state.raiders.complied {
+            0 => {}
+            1 => {
+                p.text("They said they remembered me. I guess I'm an easy target now.");
+            }
+            _ => {
+                p.text("The same faces as last time. They're getting greedier.");
+            }
+        }
+        if has_cat {
+            p.text("They even threatened to kill my cat if I didn't comply.");
+        }
+        if potato_count < requested {
+            p.text(format!(
+                "They demanded {} potatoes, I didn't have that many...",
+                requested
+            ));
+        } else {
+            p.text(format!("They demanded {} potatoes.", requested));
+        }
+
+        // hesitating counts as refusing
+        p.timed(DECISION_TIME, 1);
+        let option = p
+            .add_option("refuse", refuse.clone())
+            .color_text("Those bastards shot me", RED);
+        refuse.describe(option, health);
+
+        let option = p
+            .add_option(format!("Give {} potatoes", requested), comply.clone())
+            .enabled_if(potato_count >= requested, "not enough potatoes")
+            .text("I had no choice but to give them the potatoes");
+        comply.describe(option, health);
+
+        if potato_count > 0 {
+            let option = p.add_option("hide the food", hide.clone());
+            if hidden {
+                option
+                    .text("I told them I had nothing left.")
+                    .text("They searched the barn but didn't find my stash.");
+            } else {
+                option
+                    .text("I told them I had nothing left.")
+                    .text("They found my stash and roughed me up for lying.");
+            }
+            hide.describe(option, health);
+        }
+
+        if potato_count > 0 {
+            let option = p.add_option("negotiate", negotiate.clone());
+            if negotiated {
+                option.text("I talked them down to half. They took it and left.");
+            } else {
+                option.text("They laughed at me and took what they wanted anyway.");
+            }
+            negotiate.describe(option, health);
+        }
+
+        let option = p
+            .add_option("hold out behind the barricades", barricade.clone())
+            .enabled_if(barricades > 0, "no barricades");
+        if barricade.resisted {
+            option
+                .text("They couldn't get past my barricades.")
+                .color_text("Eventually they gave up", DARKGREEN);
+        } else {
+            option.text("They tore through the barricade and shot at me.");
+        }
+        barricade.describe(option, health);
+
+        if let Some((shot, _)) = shot {
+            let option = p.add_option("point the gun at them", gun.clone());
+            match shot {
+                Shot::Fired { hit: true, .. } => {
+                    option
+                        .text("I fired a few shots.")
+                        .text("You should've seen their faces.");
+                }
+                Shot::Fired { hit: false, .. } => {
+                    option
+                        .text("I fired at them, but my hands were shaking too much.")
+                        .text("They shot back.");
+                }
+                Shot::Jammed => {
+                    option
+                        .text("I pulled the trigger and nothing happened. The gun jammed.")
+                        .text("They didn't give me a second try.");
+                }
+                Shot::Empty if gun.resisted => {
+                    option
+                        .text("The gun wasn't loaded, but they didn't know that.")
+                        .text("They ran off.");
+                }
+                Shot::Empty => {
+                    option
+                        .text("The gun wasn't loaded.")
+                        .text("They figured that out pretty quickly.");
+                }
+            }
+            if gun.gun_wear >= gun_condition {
+                option.color_text("<The gun fell apart>", RED);
+            }
+            gun.describe(option, health);
+        }
+    });
+
+    prompt.then(|outcome, ctx| {
+        let state = ctx.state_mut();
+        state
+            .inventory
+            .remove(Item::CookedPotato, outcome.potatoes_lost);
+        state
+            .inventory
+            .remove(Item::Barricade, outcome.barricades_lost);
+        state.inventory.remove(Item::Ammo, outcome.ammo_used);
+        if let Some(gun) = state.gun.as_mut() {
+            if outcome.gun_wear > 0 && !gun.degrade(outcome.gun_wear) {
+                state.gun = None;
+            }
+        }
+        if outcome.damage > 0 && !state.health.subn(outcome.damage) {
+            state.is_dead = true;
+        }
+        if let Some(farm) = state.farm.as_mut() {
+            farm.trample(&mut state.rng, outcome.trampled);
+        }
+        if outcome.complied {
+            state.raiders.complied += 1;
+        }
+        if outcome.resisted {
+            state.raiders.resisted += 1;
+        }
+        state.raiders.last_raid = Some(state.page);
+        Transition::Pop
+    })
+}
@@ -0,0 +1,469 @@
+//! Saved games are plain text, one `key: value` per line. Lists like the inventory repeat their
+//! key for every element, and the farm is stored one row of tiles per line.
+
+use super::{
+    Blight, Cat, CatState, Compost, Farm, Gun, Item, Location, Objective, Pest, PestKind, Profile,
+    Quest, QuestGiver, Stat, State, Structure, Tile, BLIGHT_FATAL, CLEARING_DAYS, MAX_FERTILITY,
+    MAX_SIZE, POTATO_MATURE_AGE,
+};
+use crate::dialogue::Journal;
+use std::{fmt::Write, ops::RangeInclusive, str::FromStr};
+
+impl State {
+    /// Write the state in the format read by `State::from_save`
+    pub fn to_save(&self) -> String {
+        let mut out = String::new();
+        let mut line = |key: &str, value: String| {
+            let _ = writeln!(out, "{}: {}", key, value);
+        };
+        line("start page", self.start_page.to_string());
+        line("page", self.page.to_string());
+        line("health", save_stat(&self.health));
+        line("food", save_stat(&self.food));
+        line(
+            "cat",
+            match self.cat {
+                CatState::NotVisited => "not visited",
+                CatState::None => "none",
+                CatState::Cat(_) => "cat",
+            }
+            .to_owned(),
+        );
+        line("cold", self.has_a_cold.to_string());
+        line("headache", self.has_a_headache.to_string());
+        line(
+            "last cook had blight",
+            self.last_cook_had_blight.to_string(),
+        );
+        line("radiation", self.radiation.to_string());
+        if let Some(gun) = &self.gun {
+            line("gun", save_stat(&gun.condition));
+        }
+        for (item, count) in self.inventory.stacks() {
+            line("item", format!("{} {}", count, item.key()));
+        }
+        for location in Location::ALL {
+            if self.map.is_unlocked(location) {
+                line("unlocked", format!("{:?}", location));
+            }
+        }
+        for npc in self.npcs.met() {
+            line(
+                "npc",
+                format!(
+                    "{} {} {}",
+                    npc.profile.id,
+                    npc.relationship,
+                    save_option(npc.last_seen)
+                ),
+            );
+        }
+        for quest in &self.quests {
+            let giver = match quest.giver {
+                QuestGiver::Trader => "trader".to_owned(),
+                QuestGiver::Npc(index) => format!("npc {}", index),
+            };
+            let objective = match quest.objective {
+                Objective::Deliver(item, count) => format!("deliver {} {}", count, item.key()),
+                Objective::Plant(count) => format!("plant {}", count),
+            };
+            line(
+                "quest",
+                format!(
+                    "{}; {}; {}; {} {}",
+                    giver,
+                    quest.deadline,
+                    objective,
+                    quest.reward.1,
+                    quest.reward.0.key()
+                ),
+            );
+        }
+        line(
+            "raiders",
+            format!(
+                "{} {} {}",
+                self.raiders.complied,
+                self.raiders.resisted,
+                save_option(self.raiders.last_raid)
+            ),
+        );
+        if let Some(farm) = &self.farm {
+            line(
+                "compost",
+                format!(
+                    "{} {} {}",
+                    farm.compost.waste, farm.compost.rotted, farm.compost.ready
+                ),
+            );
+            line(
+                "days since last blight",
+                farm.days_since_last_blight.to_string(),
+            );
+            for y in 0..farm.height() {
+                let row: Vec<String> = (0..farm.width())
+                    .map(|x| {
+                        format!(
+                            "{}/{}/{}",
+                            save_tile(&farm.tiles[x][y]),
+                            farm.fertility[x][y],
+                            u8::from(farm.watered[x][y])
+                        )
+                    })
+                    .collect();
+                line("row", row.join(" "));
+            }
+            for pest in &farm.pests {
+                let kind = match pest.kind {
+                    PestKind::Crow => "crow",
+                    PestKind::Beetle => "beetle",
+                };
+                line("pest", format!("{} {} {}", kind, pest.x, pest.y));
+            }
+        }
+        for entry in self.journal.to_text() {
+            line("entry", String::new());
+            for text in entry {
+                line("line", text);
+            }
+        }
+        out
+    }
+
+    /// Read a state that was written by `State::to_save`
+    pub fn from_save(save: &str, people: &[Profile]) -> Result<Self, String> {
+        let mut state = State::new(0, people);
+        state.inventory.remove_all(Item::CanOfBeans);
+        let mut farm_rows = Vec::new();
+        let mut compost = None;
+        let mut days_since_last_blight = 0;
+        let mut pests = Vec::new();
+        let mut journal: Vec<Vec<String>> = Vec::new();
+
+        for (index, text) in save.lines().enumerate() {
+            let error = |e: String| format!("line {}: {}", index + 1, e);
+            let Some((key, value)) = text.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "start page" => state.start_page = parse(value).map_err(error)?,
+                "page" => state.page = parse(value).map_err(error)?,
+                "entry" => journal.push(Vec::new()),
+                "line" => match journal.last_mut() {
+                    Some(entry) => entry.push(value.to_owned()),
+                    None => return Err(error("line outside of a journal entry".to_owned())),
+                },
+                "health" => state.health = parse_stat(value).map_err(error)?,
+                "food" => state.food = parse_stat(value).map_err(error)?,
+                "cat" => {
+                    state.cat = match value {
+                        "not visited" => CatState::NotVisited,
+                        "none" => CatState::None,
+                        "cat" => CatState::Cat(Cat::default()),
+                        _ => return Err(error(format!("unknown cat {:?}", value))),
+                    }
+                }
+                "cold" => state.has_a_cold = parse(value).map_err(error)?,
+                "headache" => state.has_a_headache = parse(value).map_err(error)?,
+                "last cook had blight" => {
+                    state.last_cook_had_blight = parse(value).map_err(error)?
+                }
+                "radiation" => state.radiation = parse(value).map_err(error)?,
+                "gun" => {
+                    state.gun = Some(Gun {
+                        condition: parse_stat(value).map_err(error)?,
+                    })
+                }
+                "item" => {
+                    let (item, count) = parse_stack(value).map_err(error)?;
+                    state.inventory.add(item, count);
+                }
+                "unlocked" => {
+                    match Location::ALL
+                        .into_iter()
+                        .find(|location| format!("{:?}", location) == value)
+                    {
+                        Some(location) => state.map.unlock(location),
+                        None => return Err(error(format!("unknown location {:?}", value))),
+                    }
+                }
+                "npc" => {
+                    let mut parts = value.split_whitespace();
+                    let (Some(id), Some(relationship), Some(last_seen)) =
+                        (parts.next(), parts.next(), parts.next())
+                    else {
+                        return Err(error(format!(
+                            "expected `id relationship last_seen`, got {:?}",
+                            value
+                        )));
+                    };
+                    let npc = state
+                        .npcs
+                        .find_mut(id)
+                        .ok_or_else(|| error(format!("unknown character {:?}", id)))?;
+                    npc.relationship = parse(relationship).map_err(error)?;
+                    npc.last_seen = parse_option(last_seen).map_err(error)?;
+                }
+                "quest" => {
+                    let quest = parse_quest(value).map_err(error)?;
+                    if let QuestGiver::Npc(index) = quest.giver {
+                        if !state.npcs.has(index) {
+                            return Err(error(format!("unknown character {}", index)));
+                        }
+                    }
+                    state.quests.push(quest);
+                }
+                "raiders" => {
+                    let parts: Vec<&str> = value.split_whitespace().collect();
+                    let [complied, resisted, last_raid] = parts[..] else {
+                        return Err(error(format!("expected 3 numbers, got {:?}", value)));
+                    };
+                    state.raiders.complied = parse(complied).map_err(error)?;
+                    state.raiders.resisted = parse(resisted).map_err(error)?;
+                    state.raiders.last_raid = parse_option(last_raid).map_err(error)?;
+                }
+                "compost" => {
+                    let parts: Vec<&str> = value.split_whitespace().collect();
+                    let [waste, rotted, ready] = parts[..] else {
+                        return Err(error(format!("expected 3 numbers, got {:?}", value)));
+                    };
+                    compost = Some(Compost {
+                        waste: parse(waste).map_err(error)?,
+                        rotted: parse(rotted).map_err(error)?,
+                        ready: parse(ready).map_err(error)?,
+                    });
+                }
+                "days since last blight" => days_since_last_blight = parse(value).map_err(error)?,
+                "row" => {
+                    let row = value
+                        .split_whitespace()
+                        .map(parse_cell)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(error)?;
+                    farm_rows.push(row);
+                }
+                "pest" => {
+                    let parts: Vec<&str> = value.split_whitespace().collect();
+                    let [kind, x, y] = parts[..] else {
+                        return Err(error(format!("expected `kind x y`, got {:?}", value)));
+                    };
+                    let kind = match kind {
+                        "crow" => PestKind::Crow,
+                        "beetle" => PestKind::Beetle,
+                        _ => return Err(error(format!("unknown pest {:?}", kind))),
+                    };
+                    pests.push(Pest {
+                        kind,
+                        x: parse(x).map_err(error)?,
+                        y: parse(y).map_err(error)?,
+                    });
+                }
+                _ => return Err(error(format!("unknown key {:?}", key))),
+            }
+        }
+
+        if state.page < state.start_page {
+            return Err(format!(
+                "page {} is before the start page {}",
+                state.page, state.start_page
+            ));
+        }
+        if let Some(compost) = compost {
+            let width = farm_rows.first().map(Vec::len).unwrap_or_default();
+            let height = farm_rows.len();
+            if width == 0 || farm_rows.iter().any(|row| row.len() != width) {
+                return Err("the farm is not rectangular".to_owned());
+            }
+            if width > MAX_SIZE || height > MAX_SIZE {
+                return Err(format!(
+                    "the farm is {}x{}, but can be at most {}x{}",
+                    width, height, MAX_SIZE, MAX_SIZE
+                ));
+            }
+            if let Some(pest) = pests
+                .iter()
+                .find(|pest| pest.x >= width || pest.y >= height)
+            {
+                return Err(format!("pest at {}/{} is outside the farm", pest.x, pest.y));
+            }
+            // the rows are stored top to bottom, but the farm is indexed by column first
+            let column = |x: usize| farm_rows.iter().map(move |row: &Vec<_>| &row[x]);
+            state.farm = Some(Farm {
+                tiles: (0..width)
+                    .map(|x| column(x).map(|(tile, _, _)| tile.clone()).collect())
+                    .collect(),
+                fertility: (0..width)
+                    .map(|x| column(x).map(|(_, fertility, _)| *fertility).collect())
+                    .collect(),
+                watered: (0..width)
+                    .map(|x| column(x).map(|(_, _, watered)| *watered).collect())
+                    .collect(),
+                compost,
+                pests,
+                days_since_last_blight,
+            });
+        }
+        state.journal = Journal::from_text(journal);
+        Ok(state)
+    }
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("could not parse {:?}", value))
+}
+
+/// Parse a number that has to be within `range`, like the age of a plant
+fn parse_in(value: &str, range: RangeInclusive<u8>) -> Result<u8, String> {
+    let number = parse(value)?;
+    if range.contains(&number) {
+        Ok(number)
+    } else {
+        Err(format!(
+            "{} is not between {} and {}",
+            number,
+            range.start(),
+            range.end()
+        ))
+    }
+}
+
+fn save_option(value: Option<u32>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "-".to_owned(),
+    }
+}
+
+fn parse_option(value: &str) -> Result<Option<u32>, String> {
+    match value {
+        "-" => Ok(None),
+        value => parse(value).map(Some),
+    }
+}
+
+fn save_stat(stat: &Stat) -> String {
+    format!("{}/{}", stat.current, stat.max)
+}
+
+fn parse_stat(value: &str) -> Result<Stat, String> {
+    let (current, max) = value
+        .split_once('/')
+        .ok_or_else(|| format!("expected `current/max`, got {:?}", value))?;
+    Ok(Stat {
+        current: parse(current)?,
+        max: parse(max)?,
+    })
+}
+
+/// Parse `count item`, e.g. `5 cooked potato`
+fn parse_stack(value: &str) -> Result<(Item, usize), String> {
+    let (count, item) = value
+        .split_once(' ')
+        .ok_or_else(|| format!("expected `count item`, got {:?}", value))?;
+    Ok((item.parse()?, parse(count)?))
+}
+
+fn parse_quest(value: &str) -> Result<Quest, String> {
+    let parts: Vec<&str> = value.split(';').map(str::trim).collect();
+    let [giver, deadline, objective, reward] = parts[..] else {
+        return Err(format!(
+            "expected `giver; deadline; objective; reward`, got {:?}",
+            value
+        ));
+    };
+    let giver = match giver.strip_prefix("npc ") {
+        Some(index) => QuestGiver::Npc(parse(index)?),
+        None if giver == "trader" => QuestGiver::Trader,
+        None => return Err(format!("unknown quest giver {:?}", giver)),
+    };
+    let objective = if let Some(stack) = objective.strip_prefix("deliver ") {
+        let (item, count) = parse_stack(stack)?;
+        Objective::Deliver(item, count)
+    } else if let Some(count) = objective.strip_prefix("plant ") {
+        Objective::Plant(parse(count)?)
+    } else {
+        return Err(format!("unknown objective {:?}", objective));
+    };
+    Ok(Quest {
+        giver,
+        objective,
+        deadline: parse(deadline)?,
+        reward: parse_stack(reward)?,
+    })
+}
+
+/// A tile as a short word, e.g. `dirt`, `potato5` or `potato5b2` for a plant with blight
+fn save_tile(tile: &Tile) -> String {
+    match tile {
+        Tile::Dirt => "dirt".to_owned(),
+        Tile::Potato { age, blight: None } => format!("potato{}", age),
+        Tile::Potato {
+            age,
+            blight: Some(blight),
+        } => format!(
+            "potato{}{}{}",
+            age,
+            if blight.spotted { "B" } else { "b" },
+            blight.days
+        ),
+        Tile::Overgrown => "overgrown".to_owned(),
+        Tile::Clearing { days_left } => format!("clearing{}", days_left),
+        Tile::Structure(structure) => structure.item().key().replace(' ', "-"),
+    }
+}
+
+fn parse_tile(value: &str) -> Result<Tile, String> {
+    if let Some(potato) = value.strip_prefix("potato") {
+        let (age, blight) = match potato.find(['b', 'B']) {
+            Some(index) => {
+                let blight = Blight {
+                    // the plant would have died of it
+                    days: parse_in(&potato[index + 1..], 0..=BLIGHT_FATAL - 1)?,
+                    spotted: potato[index..].starts_with('B'),
+                };
+                (&potato[..index], Some(blight))
+            }
+            None => (potato, None),
+        };
+        return Ok(Tile::Potato {
+            age: parse_in(age, 0..=POTATO_MATURE_AGE)?,
+            blight,
+        });
+    }
+    if let Some(days_left) = value.strip_prefix("clearing") {
+        return Ok(Tile::Clearing {
+            days_left: parse_in(days_left, 1..=CLEARING_DAYS)?,
+        });
+    }
+    match value {
+        "dirt" => Ok(Tile::Dirt),
+        "overgrown" => Ok(Tile::Overgrown),
+        _ => value
+            .replace('-', " ")
+            .parse()
+            .ok()
+            .and_then(Structure::from_item)
+            .map(Tile::Structure)
+            .ok_or_else(|| format!("unknown tile {:?}", value)),
+    }
+}
+
+/// Parse `tile/fertility/watered`
+fn parse_cell(value: &str) -> Result<(Tile, u8, bool), String> {
+    let parts: Vec<&str> = value.split('/').collect();
+    let [tile, fertility, watered] = parts[..] else {
+        return Err(format!(
+            "expected `tile/fertility/watered`, got {:?}",
+            value
+        ));
+    };
+    Ok((
+        parse_tile(tile)?,
+        parse_in(fertility, 0..=MAX_FERTILITY)?,
+        watered == "1",
+    ))
+}
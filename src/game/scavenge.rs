@@ -0,0 +1,237 @@
+use super::{Activity, Item, State};
+use crate::{
+    dialogue::{Dialogue, DialogueBuilder, Prompt},
+    scene::{Scene, Transition},
+};
+use macroquad::prelude::{DARKGREEN, RED, YELLOW};
+use rand::Rng;
+
+static TOWN: Site = Site {
+    name: "the town",
+    description: "the town (dangerous, lots of supplies)",
+    arrival: "I walked all the way into town.",
+    injury_chance: 0.3,
+    injury: (10, 25),
+    radiation: (5, 15),
+    loot: &[
+        Loot {
+            item: Item::CanOfBeans,
+            chance: 0.6,
+            min: 1,
+            max: 4,
+        },
+        Loot {
+            item: Item::Scrap,
+            chance: 0.7,
+            min: 2,
+            max: 6,
+        },
+        Loot {
+            item: Item::Wood,
+            chance: 0.3,
+            min: 1,
+            max: 3,
+        },
+    ],
+};
+
+static NEIGHBOURS_FARM: Site = Site {
+    name: "the neighbour's farm",
+    description: "the neighbour's farm (safe, few supplies)",
+    arrival: "I went over to the neighbour's farm.",
+    injury_chance: 0.05,
+    injury: (5, 10),
+    radiation: (0, 3),
+    loot: &[
+        Loot {
+            item: Item::Seeds,
+            chance: 0.5,
+            min: 2,
+            max: 5,
+        },
+        Loot {
+            item: Item::RawPotato,
+            chance: 0.4,
+            min: 1,
+            max: 6,
+        },
+        Loot {
+            item: Item::Wood,
+            chance: 0.6,
+            min: 2,
+            max: 5,
+        },
+    ],
+};
+
+static RUINED_STORE: Site = Site {
+    name: "the ruined store",
+    description: "the ruined store (risky, mostly food)",
+    arrival: "I climbed into what's left of the store down the road.",
+    injury_chance: 0.15,
+    injury: (5, 20),
+    radiation: (3, 10),
+    loot: &[
+        Loot {
+            item: Item::CanOfBeans,
+            chance: 0.8,
+            min: 1,
+            max: 3,
+        },
+        Loot {
+            item: Item::Seeds,
+            chance: 0.2,
+            min: 1,
+            max: 3,
+        },
+        Loot {
+            item: Item::Scrap,
+            chance: 0.3,
+            min: 1,
+            max: 2,
+        },
+    ],
+};
+
+static SITES: [&Site; 3] = [&TOWN, &NEIGHBOURS_FARM, &RUINED_STORE];
+
+struct Site {
+    name: &'static str,
+    description: &'static str,
+    arrival: &'static str,
+    injury_chance: f64,
+    injury: (u32, u32),
+    radiation: (u32, u32),
+    loot: &'static [Loot],
+}
+
+struct Loot {
+    item: Item,
+    chance: f64,
+    min: usize,
+    max: usize,
+}
+
+struct Outcome {
+    loot: Vec<(Item, usize)>,
+    injury: Option<u32>,
+    radiation: u32,
+}
+
+impl Site {
+    fn roll(&self, state: &mut State) -> Outcome {
+        let rng = &mut state.rng;
+        let mut loot = Vec::new();
+        for l in self.loot {
+            if rng.gen_bool(l.chance) {
+                loot.push((l.item, rng.gen_range(l.min..=l.max)));
+            }
+        }
+
+        // being sick makes it a lot harder to get away from trouble
+        let injury_chance = if state.has_a_cold {
+            (self.injury_chance * 1.5).min(1.0)
+        } else {
+            self.injury_chance
+        };
+        let injury = if rng.gen_bool(injury_chance) {
+            Some(rng.gen_range(self.injury.0..=self.injury.1))
+        } else {
+            None
+        };
+        let radiation = rng.gen_range(self.radiation.0..=self.radiation.1);
+
+        Outcome {
+            loot,
+            injury,
+            radiation,
+        }
+    }
+
+    /// Write how the trip went into the diary, with `health` being what the farmer set out with
+    fn describe(&self, outcome: &Outcome, health: u32, d: &mut impl DialogueBuilder) {
+        d.text(self.arrival);
+        if outcome.loot.is_empty() {
+            d.text("Someone must have beaten me to it, I didn't find anything.");
+        }
+        for (item, count) in &outcome.loot {
+            d.color_text(format!("Found {} x {}", count, item.name()), YELLOW);
+        }
+        match outcome.injury {
+            Some(damage) if damage >= health => {
+                d.jiggle_color_text(
+                    format!(
+                        "I got badly hurt in {}. I don't think I'll make it.",
+                        self.name
+                    ),
+                    RED,
+                );
+            }
+            Some(_) => {
+                d.text(format!("I hurt myself climbing around {}.", self.name))
+                    .color_text("<Lost health>", RED);
+            }
+            None => {
+                d.color_text("Made it back in one piece", DARKGREEN);
+            }
+        }
+        if outcome.radiation > 5 {
+            d.text("My skin has been tingling ever since I got back...");
+        }
+    }
+
+    /// Spend the day scavenging here
+    fn visit(&self, state: &mut State) -> Dialogue {
+        let health = state.health.current;
+        let outcome = self.roll(state);
+        let dialogue = Dialogue::new(|d| {
+            d.page(state.page);
+            self.describe(&outcome, health, d);
+        });
+        outcome.apply(state);
+        dialogue
+    }
+}
+
+impl Outcome {
+    fn apply(&self, state: &mut State) {
+        for (item, count) in &self.loot {
+            state.inventory.add(*item, *count);
+        }
+        if let Some(damage) = self.injury {
+            if !state.health.subn(damage) {
+                state.is_dead = true;
+            }
+        }
+        state.radiation += self.radiation;
+    }
+}
+
+/// Pick one of the locations around the farm to scavenge for the rest of the day. Staying home
+/// leaves the day as it was.
+pub fn scavenge(state: &State) -> impl Scene {
+    let prompt = Prompt::new(|p| {
+        p.page(state.page);
+        p.text("I decided to go out and look for supplies.");
+        p.text("Where should I go?");
+        p.add_numbered_option(0, "stay home", None)
+            .text("On second thought, it's not safe out there.");
+
+        for (index, site) in SITES.iter().enumerate() {
+            p.add_option(site.description, Some(index));
+        }
+    });
+
+    prompt.then(|choice, ctx| match choice {
+        Some(index) => {
+            let trip = SITES[index].visit(ctx.state_mut());
+            Transition::replace(Activity::new(Box::new(trip)))
+        }
+        None => Transition::Pop,
+    })
+}
+
+/// Spend the day scavenging in town, which the farmer can walk to once it's on the map
+pub fn scavenge_town(state: &mut State) -> Dialogue {
+    TOWN.visit(state)
+}
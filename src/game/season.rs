@@ -0,0 +1,35 @@
+/// Days in a single season
+const SEASON_LENGTH: u32 = 10;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn of(page: u32) -> Self {
+        match (page / SEASON_LENGTH) % 4 {
+            0 => Self::Spring,
+            1 => Self::Summer,
+            2 => Self::Autumn,
+            _ => Self::Winter,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Spring => "Spring",
+            Self::Summer => "Summer",
+            Self::Autumn => "Autumn",
+            Self::Winter => "Winter",
+        }
+    }
+
+    /// Nothing grows in the cold, unless it's in a greenhouse
+    pub fn allows_growth(&self) -> bool {
+        !matches!(self, Self::Winter)
+    }
+}
@@ -0,0 +1,85 @@
+use super::Item;
+use macroquad::prelude::*;
+
+/// Something the farmer built on the farm. Every structure takes up a single tile.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Structure {
+    /// Plants next to the greenhouse keep growing in winter
+    Greenhouse,
+    /// Food keeps a lot longer in the cellar
+    RootCellar,
+    /// Plants next to the well get watered every day
+    Well,
+    /// Keeps crows away from the plants around it
+    Scarecrow,
+}
+
+impl Structure {
+    pub fn from_item(item: Item) -> Option<Self> {
+        match item {
+            Item::Greenhouse => Some(Self::Greenhouse),
+            Item::RootCellar => Some(Self::RootCellar),
+            Item::Well => Some(Self::Well),
+            Item::Scarecrow => Some(Self::Scarecrow),
+            _ => None,
+        }
+    }
+
+    pub fn item(&self) -> Item {
+        match self {
+            Self::Greenhouse => Item::Greenhouse,
+            Self::RootCellar => Item::RootCellar,
+            Self::Well => Item::Well,
+            Self::Scarecrow => Item::Scarecrow,
+        }
+    }
+
+    /// How many tiles away the structure has an effect on the plants around it
+    pub fn range(&self) -> usize {
+        match self {
+            Self::RootCellar => 0,
+            Self::Greenhouse | Self::Well => 1,
+            Self::Scarecrow => 2,
+        }
+    }
+
+    pub fn draw_at(&self, x: f32, y: f32, size: f32) {
+        match self {
+            Self::Greenhouse => {
+                draw_rectangle(x + 2., y + 2., size - 4., size - 4., LIGHTGRAY);
+                draw_rectangle(x + 5., y + 5., size - 10., size - 10., SKYBLUE);
+                draw_line(
+                    x + size / 2.,
+                    y + 2.,
+                    x + size / 2.,
+                    y + size - 2.,
+                    2.,
+                    GRAY,
+                );
+                draw_line(
+                    x + 2.,
+                    y + size / 2.,
+                    x + size - 2.,
+                    y + size / 2.,
+                    2.,
+                    GRAY,
+                );
+            }
+            Self::RootCellar => {
+                draw_rectangle(x, y, size, size, DARKGREEN);
+                draw_rectangle(x + 10., y + 15., size - 20., size - 20., DARKBROWN);
+                draw_rectangle_lines(x + 10., y + 15., size - 20., size - 20., 2., BROWN);
+            }
+            Self::Well => {
+                draw_circle(x + size / 2., y + size / 2., size / 2. - 4., GRAY);
+                draw_circle(x + size / 2., y + size / 2., size / 2. - 10., DARKBLUE);
+            }
+            Self::Scarecrow => {
+                draw_rectangle(x + size / 2. - 2., y + 8., 4., size - 10., BROWN);
+                draw_rectangle(x + 8., y + 18., size - 16., 4., BROWN);
+                draw_circle(x + size / 2., y + 10., 7., BEIGE);
+                draw_rectangle(x + size / 2. - 9., y + 2., 18., 4., DARKBROWN);
+            }
+        }
+    }
+}
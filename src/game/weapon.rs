@@ -0,0 +1,80 @@
+use super::Stat;
+use ::rand::Rng;
+
+/// Below this condition the gun starts jamming
+const JAM_CONDITION: u32 = 40;
+/// Condition lost for every bullet fired
+const WEAR_PER_SHOT: u32 = 4;
+/// Condition lost when the gun jams
+const WEAR_PER_JAM: u32 = 8;
+
+pub struct Gun {
+    pub condition: Stat,
+}
+
+impl Default for Gun {
+    fn default() -> Self {
+        Self {
+            condition: Stat::new(100),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Shot {
+    /// No bullets left, all the farmer can do is bluff
+    Empty,
+    Jammed,
+    Fired {
+        shots: usize,
+        hit: bool,
+    },
+}
+
+impl Shot {
+    pub fn ammo_used(&self) -> usize {
+        match self {
+            Self::Fired { shots, .. } => *shots,
+            _ => 0,
+        }
+    }
+}
+
+impl Gun {
+    fn jam_chance(&self) -> f64 {
+        if self.condition.current < JAM_CONDITION {
+            0.1 + 0.6 * (JAM_CONDITION - self.condition.current) as f64 / JAM_CONDITION as f64
+        } else {
+            0.02
+        }
+    }
+
+    /// Try to fire the gun. A worn gun jams more often, and a hurt farmer has a shaky aim.
+    pub fn fire(&self, ammo: usize, health: &Stat, rng: &mut impl Rng) -> Shot {
+        if ammo == 0 {
+            return Shot::Empty;
+        }
+        if rng.gen_bool(self.jam_chance()) {
+            return Shot::Jammed;
+        }
+        let shots = rng.gen_range(1..=3).min(ammo);
+        let aim = 0.4 + 0.55 * health.current as f64 / health.max as f64;
+        Shot::Fired {
+            shots,
+            hit: rng.gen_bool(aim),
+        }
+    }
+
+    pub fn wear(&self, shot: Shot) -> u32 {
+        match shot {
+            Shot::Empty => 0,
+            Shot::Jammed => WEAR_PER_JAM,
+            Shot::Fired { shots, .. } => shots as u32 * WEAR_PER_SHOT,
+        }
+    }
+
+    /// Damage the gun. Returns `false` if the gun fell apart.
+    pub fn degrade(&mut self, wear: u32) -> bool {
+        self.condition.subn(wear)
+    }
+}
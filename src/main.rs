@@ -1,183 +1,116 @@
 mod assets;
 mod dialogue;
+mod effects;
 mod game;
+mod pause;
+mod scene;
+mod settings;
 
 use assets::Assets;
-use dialogue::{Dialogue, DialogueBuilder, DialogueOpts, Event, FrameCtx};
-use game::{DayAction, Farm, Item, State};
+use dialogue::{Dialogue, DialogueBuilder};
+use game::{Day, State};
 use macroquad::prelude::*;
+use scene::{Ctx, Scene, SceneStack, Title, Transition};
 
 #[macroquad::main("Potat")]
 async fn main() {
     let assets = Assets::new().await;
 
-    'restart: loop {
-        #[cfg(not(debug_assertions))]
-        let mut state = intro().await;
-        #[cfg(not(debug_assertions))]
-        let mut first_event = None;
+    SceneStack::new(Title::default()).run(&assets).await;
+}
 
-        #[cfg(debug_assertions)]
-        let (mut state, mut first_event) = loop {
-            clear_background(BLACK);
-            next_frame().await;
-            if is_key_pressed(KeyCode::Enter) {
-                break (intro().await, None);
-            }
-            if is_key_pressed(KeyCode::F1) {
-                let mut state = State::new(1);
-                state.inventory.add(Item::Seeds, 10);
-                state.inventory.add(Item::CookedPotato, 10000);
-                state.farm = Some(Farm::default());
-                state.has_a_cold = true;
-                state.page = 8;
-                break (state, None);
-            }
-        };
+/// The first diary pages, written in the bunker before the game starts
+struct Intro {
+    page: u32,
+    dialogue: Dialogue,
+}
 
-        loop {
-            let event = first_event
-                .take()
-                .unwrap_or_else(|| game::next_event(&state));
-            event.dialogue(&mut state).await;
-            if state.is_dead {
-                loop {
-                    next_frame().await;
-                    clear_background(BLACK);
-                    draw_text_centered("You died", screen_width() / 2.0, 50., 50., WHITE);
-                    draw_text(
-                        &format!("Survived {} days", state.page),
-                        50.,
-                        100.,
-                        24.,
-                        WHITE,
-                    );
-                    draw_text("<Enter> restart", 50., screen_height() - 50., 30., WHITE);
-                    if is_key_pressed(KeyCode::Enter) {
-                        continue 'restart;
-                    }
-                }
-            }
-            match state.draw(event, &assets).await {
-                DayAction::Farm => {
-                    if let Some(mut farm) = state.farm.take() {
-                        farm.draw(&mut state, &assets).await;
-                        state.farm = Some(farm);
-                    }
-                }
-                DayAction::Next => {}
-            }
-            state.end_of_day();
+impl Intro {
+    /// Start a new game with the intro, so its pages end up in the journal as they are read
+    fn start(ctx: &mut Ctx) -> Self {
+        ctx.game = Some(State::new(5, &ctx.assets.people));
+        Self {
+            page: 1,
+            dialogue: intro_page(1).expect("The intro has no pages"),
         }
     }
 }
 
-async fn intro() -> State {
-    let mut skip_intro = false;
-    let mut opts = DialogueOpts {
-        events: Some(|ctx: FrameCtx| {
-            if skip_intro {
-                Event::Done
-            } else if is_key_pressed(KeyCode::Escape) {
-                skip_intro = true;
-                Event::Done
-            } else if ctx.all_text_visible {
-                if is_key_pressed(KeyCode::Enter) {
-                    Event::Done
-                } else {
-                    Event::Idle
+impl Scene for Intro {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        if is_key_pressed(KeyCode::S) {
+            self.dialogue.record(&mut ctx.state_mut().journal);
+            return Transition::replace(Day::default());
+        }
+        match self.dialogue.update(ctx) {
+            // the page was closed
+            Transition::Pop => match intro_page(self.page + 1) {
+                Some(dialogue) => {
+                    self.page += 1;
+                    self.dialogue = dialogue;
+                    Transition::Stay
                 }
-            } else if is_key_pressed(KeyCode::Space) {
-                Event::ShowText
-            } else {
-                Event::NextChar
-            }
-        }),
-        intro: true,
-        ..Default::default()
-    };
-    Dialogue::new(|d| {
-        d.page(1);
-        d.text("Uh. Dear diary? I guess?");
-        d.text("Today was shit.");
-        d.text("I was in my potato field like normal, when the sirens started ringing.");
-        d.jiggle_text("It was terr.. terrif.. scary!");
-        d.text("Luckily we had that shelter training last week.");
-        d.text("I didn't get hurt, luckily, but the ground shook.");
-        d.text("Anyway I'm now stuck in here.");
-        d.text("See you tomorrow, I guess?");
-        d.text("This diary thing is complicated");
-    })
-    .render_with_opts(&mut opts)
-    .await;
-
-    Dialogue::new(|d| {
-        d.page(2);
-        d.text("Still stuck in the bunker.");
-        d.text("");
-        d.text("Oh right, dear diary.");
-        d.text("Still stuck in the bunker.");
-        d.text("I'm not sure when to go out.");
-        d.text("");
-        d.text("The beans I had were tasty.");
-        d.text("");
-        d.text("See you tomorrow?");
-    })
-    .render_with_opts(&mut opts)
-    .await;
+                None => Transition::replace(Day::default()),
+            },
+            transition => transition,
+        }
+    }
 
-    Dialogue::new(|d| {
-        d.page(3);
-        d.text("At least I've been able to catch up on sleep.");
-    })
-    .render_with_opts(&mut opts)
-    .await;
+    fn draw(&mut self, ctx: &Ctx) {
+        self.dialogue.draw(ctx);
+        draw_text(
+            "<S> skip intro",
+            screen_width() - 200.,
+            screen_height() - 50.,
+            24.,
+            WHITE,
+        );
+    }
+}
 
-    Dialogue::new(|d| {
-        d.page(4);
-        d.text("I'm so bored.");
-        d.text("Tomorrow I'll go back to my barn.");
-        d.text("I'd rather die of radiation than sit in here for the rest of my life.");
-        d.text("");
-        d.text("I need some coffee.");
+fn intro_page(page: u32) -> Option<Dialogue> {
+    Some(match page {
+        1 => Dialogue::new(|d| {
+            d.page(1);
+            d.text("Uh. Dear diary? I guess?");
+            d.text("Today was shit.");
+            d.text("I was in my potato field like normal, when the sirens started ringing.");
+            d.jiggle_text("It was terr.. terrif.. scary!");
+            d.text("Luckily we had that shelter training last week.");
+            d.text("I didn't get hurt, luckily, but the ground shook.");
+            d.text("Anyway I'm now stuck in here.");
+            d.text("See you tomorrow, I guess?");
+            d.text("This diary thing is complicated");
+        }),
+        2 => Dialogue::new(|d| {
+            d.page(2);
+            d.text("Still stuck in the bunker.");
+            d.text("");
+            d.text("Oh right, dear diary.");
+            d.text("Still stuck in the bunker.");
+            d.text("I'm not sure when to go out.");
+            d.text("");
+            d.text("The beans I had were tasty.");
+            d.text("");
+            d.text("See you tomorrow?");
+        }),
+        3 => Dialogue::new(|d| {
+            d.page(3);
+            d.text("At least I've been able to catch up on sleep.");
+        }),
+        4 => Dialogue::new(|d| {
+            d.page(4);
+            d.text("I'm so bored.");
+            d.text("Tomorrow I'll go back to my barn.");
+            d.text("I'd rather die of radiation than sit in here for the rest of my life.");
+            d.text("");
+            d.text("I need some coffee.");
+        }),
+        _ => return None,
     })
-    .render_with_opts(&mut opts)
-    .await;
-
-    State::new(5)
 }
 
 fn draw_text_centered(text: &str, x: f32, y: f32, font_size: f32, color: Color) {
     let size = measure_text(text, None, font_size as u16, 1.0);
     draw_text(text, x - size.width / 2., y, font_size, color);
 }
-
-pub async fn quit_dialogue() {
-    next_frame().await;
-    loop {
-        clear_background(BLACK);
-        draw_text_centered(
-            "Do you want to quit?",
-            screen_width() / 2.,
-            300.,
-            50.,
-            WHITE,
-        );
-        draw_text_centered("<Esc> no", screen_width() / 2., 350., 50., WHITE);
-        draw_text_centered("<Enter> yes", screen_width() / 2., 400., 50., WHITE);
-        draw_text(
-            "Note: Saving is not implemented yet. You'll have to start from scratch",
-            50.,
-            500.,
-            30.,
-            WHITE,
-        );
-        if is_key_pressed(KeyCode::Enter) {
-            std::process::exit(0);
-        }
-        if is_key_pressed(KeyCode::Escape) {
-            break;
-        }
-        next_frame().await;
-    }
-}
@@ -0,0 +1,135 @@
+use crate::{
+    dialogue::get_num_pressed,
+    draw_text_centered,
+    scene::{load_game, Ctx, Scene, Title, Transition},
+    settings::SettingsScreen,
+};
+use macroquad::prelude::*;
+use std::fs;
+
+/// Where the game is saved
+const SAVE_PATH: &str = "save.txt";
+
+pub fn saved_game() -> Option<String> {
+    fs::read_to_string(SAVE_PATH).ok()
+}
+
+/// Whether there is a saved game, without reading it
+pub fn has_saved_game() -> bool {
+    fs::metadata(SAVE_PATH).is_ok()
+}
+
+/// The menu that opens with `<Esc>` during a game. `<Esc>` doesn't do anything else in a game.
+///
+/// It's drawn over the screen it paused.
+pub struct PauseMenu {
+    /// The game to save. Only the day view has one, anywhere else the farmer is in the middle of
+    /// something that a save can't hold.
+    save: Option<String>,
+    /// Whether there is a saved game to load, checked when the menu opens and after saving
+    has_save: bool,
+    message: Option<(String, Color)>,
+}
+
+impl Default for PauseMenu {
+    fn default() -> Self {
+        Self {
+            save: None,
+            has_save: has_saved_game(),
+            message: None,
+        }
+    }
+}
+
+impl PauseMenu {
+    /// A pause menu that can save the game in the format of `State::to_save`
+    pub fn with_save(save: String) -> Self {
+        Self {
+            save: Some(save),
+            ..Self::default()
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let save = self
+            .save
+            .as_ref()
+            .ok_or("The game can only be saved from the day view")?;
+        fs::write(SAVE_PATH, save).map_err(|e| format!("Could not save: {}", e))
+    }
+}
+
+impl Scene for PauseMenu {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        match get_num_pressed() {
+            Some(1) => return Transition::Pop,
+            Some(2) => return Transition::push(SettingsScreen),
+            Some(3) => {
+                self.message = Some(match self.save() {
+                    Ok(()) => ("Saved the game".to_owned(), DARKGREEN),
+                    Err(e) => (e, RED),
+                });
+                self.has_save = has_saved_game();
+            }
+            Some(4) => match saved_game().map(|save| load_game(&save, ctx)) {
+                Some(Ok(transition)) => return transition,
+                Some(Err(e)) => self.message = Some((e, RED)),
+                None => self.message = Some(("There is no saved game".to_owned(), RED)),
+            },
+            Some(5) => {
+                ctx.game = None;
+                return Transition::reset(Title::default());
+            }
+            _ => {}
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            Transition::Pop
+        } else {
+            Transition::Stay
+        }
+    }
+
+    fn draw(&mut self, _ctx: &Ctx) {
+        draw_rectangle(
+            0.,
+            0.,
+            screen_width(),
+            screen_height(),
+            Color::new(0., 0., 0., 0.75),
+        );
+        draw_text_centered("Paused", screen_width() / 2., 100., 50., WHITE);
+
+        // options that can't be chosen right now come with the reason why
+        let options = [
+            ("Resume", None),
+            ("Settings", None),
+            (
+                "Save",
+                self.save.is_none().then_some("only from the day view"),
+            ),
+            ("Load", (!self.has_save).then_some("no saved game")),
+            ("Quit to title", None),
+        ];
+        for (index, (option, disabled)) in options.iter().enumerate() {
+            let (text, color) = match disabled {
+                Some(reason) => (format!("<{}> {} ({})", index + 1, option, reason), GRAY),
+                None => (format!("<{}> {}", index + 1, option), WHITE),
+            };
+            draw_text(
+                &text,
+                screen_width() / 2. - 100.,
+                180. + index as f32 * 40.,
+                30.,
+                color,
+            );
+        }
+        if let Some((text, color)) = &self.message {
+            draw_text_centered(text, screen_width() / 2., 420., 24., *color);
+        }
+        draw_text("<Esc> resume", 50., screen_height() - 50., 24., WHITE);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{
+    assets::Assets,
+    dialogue::Seen,
+    draw_text_centered,
+    game::{Day, State},
+    pause,
+    settings::Settings,
+    Intro,
+};
+use macroquad::prelude::*;
+
+/// A screen of the game. Scenes are kept on a stack, and only the one on top runs. Every frame it
+/// is updated once and then drawn.
+pub trait Scene {
+    /// Handle the input of a single frame
+    fn update(&mut self, ctx: &mut Ctx) -> Transition;
+
+    fn draw(&mut self, ctx: &Ctx);
+
+    /// Whether the scene below stays visible behind this one
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+/// What the scenes share
+pub struct Ctx<'a> {
+    pub assets: &'a Assets,
+    pub settings: Settings,
+    pub seen: Seen,
+    /// The game that is being played, if one was started
+    pub game: Option<State>,
+}
+
+impl Ctx<'_> {
+    /// The game that is being played. Only scenes that are part of a game may call this.
+    pub fn state(&self) -> &State {
+        self.game.as_ref().expect("No game is being played")
+    }
+
+    pub fn state_mut(&mut self) -> &mut State {
+        self.game.as_mut().expect("No game is being played")
+    }
+}
+
+/// What should happen to the scene stack after a scene was updated
+pub enum Transition {
+    Stay,
+    /// Put a scene on top of the current one, the current one continues once it is popped
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+    /// Close every scene and start over with this one
+    Reset(Box<dyn Scene>),
+    /// Close every scene, which ends the game
+    Quit,
+}
+
+impl Transition {
+    pub fn push(scene: impl Scene + 'static) -> Self {
+        Self::Push(Box::new(scene))
+    }
+
+    pub fn replace(scene: impl Scene + 'static) -> Self {
+        Self::Replace(Box::new(scene))
+    }
+
+    pub fn reset(scene: impl Scene + 'static) -> Self {
+        Self::Reset(Box::new(scene))
+    }
+}
+
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new(scene: impl Scene + 'static) -> Self {
+        Self {
+            scenes: vec![Box::new(scene)],
+        }
+    }
+
+    /// Run the scene on top of the stack until there are no scenes left
+    pub async fn run(mut self, assets: &Assets) {
+        let mut ctx = Ctx {
+            assets,
+            settings: Settings::default(),
+            seen: Seen::load(),
+            game: None,
+        };
+        while let Some(scene) = self.scenes.last_mut() {
+            match scene.update(&mut ctx) {
+                Transition::Stay => {}
+                Transition::Push(scene) => self.scenes.push(scene),
+                Transition::Pop => {
+                    self.scenes.pop();
+                }
+                Transition::Replace(scene) => {
+                    self.scenes.pop();
+                    self.scenes.push(scene);
+                }
+                Transition::Reset(scene) => {
+                    self.scenes.clear();
+                    self.scenes.push(scene);
+                }
+                Transition::Quit => self.scenes.clear(),
+            }
+            // draw from the topmost scene that covers the whole screen upwards
+            let first = self
+                .scenes
+                .iter()
+                .rposition(|scene| !scene.is_overlay())
+                .unwrap_or(0);
+            clear_background(BLACK);
+            for scene in &mut self.scenes[first..] {
+                scene.draw(&ctx);
+            }
+            // keys stay pressed until the next frame, so a scene that was just opened would see
+            // the same key press that opened it
+            next_frame().await;
+        }
+    }
+}
+
+/// Continue a game from a save in the format of `State::to_save`
+pub fn load_game(save: &str, ctx: &mut Ctx) -> Result<Transition, String> {
+    let state = State::from_save(save, &ctx.assets.people)?;
+    let day = Day::resume(&state);
+    ctx.game = Some(state);
+    Ok(Transition::reset(day))
+}
+
+/// Starting a new game
+pub struct Title {
+    /// Whether there is a saved game to continue, checked when the title screen opens
+    has_save: bool,
+}
+
+impl Default for Title {
+    fn default() -> Self {
+        Self {
+            has_save: pause::has_saved_game(),
+        }
+    }
+}
+
+impl Scene for Title {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        if is_key_pressed(KeyCode::Enter) {
+            return Transition::replace(Intro::start(ctx));
+        }
+        if is_key_pressed(KeyCode::L) {
+            if let Some(save) = pause::saved_game() {
+                match load_game(&save, ctx) {
+                    Ok(transition) => return transition,
+                    Err(e) => eprintln!("Could not load the saved game: {}", e),
+                }
+            }
+        }
+        #[cfg(debug_assertions)]
+        if is_key_pressed(KeyCode::F1) {
+            ctx.game = Some(debug_state(ctx.assets));
+            return Transition::replace(Day::default());
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            Transition::push(ConfirmQuit)
+        } else {
+            Transition::Stay
+        }
+    }
+
+    fn draw(&mut self, _ctx: &Ctx) {
+        draw_text_centered("Potat", screen_width() / 2., 200., 80., WHITE);
+        draw_text_centered("<Enter> new game", screen_width() / 2., 300., 30., WHITE);
+        if self.has_save {
+            draw_text_centered("<L> continue", screen_width() / 2., 340., 30., WHITE);
+        }
+        draw_text("<Esc> quit", 50., screen_height() - 50., 24., WHITE);
+    }
+}
+
+/// The farmer died after this many days
+pub struct GameOver {
+    pub days: u32,
+}
+
+impl Scene for GameOver {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        if is_key_pressed(KeyCode::Enter) {
+            ctx.game = None;
+            Transition::replace(Title::default())
+        } else {
+            Transition::Stay
+        }
+    }
+
+    fn draw(&mut self, _ctx: &Ctx) {
+        draw_text_centered("You died", screen_width() / 2.0, 50., 50., WHITE);
+        draw_text(
+            &format!("Survived {} days", self.days),
+            50.,
+            100.,
+            24.,
+            WHITE,
+        );
+        draw_text("<Enter> restart", 50., screen_height() - 50., 30., WHITE);
+    }
+}
+
+/// Asking the player whether they really want to quit the game
+pub struct ConfirmQuit;
+
+impl Scene for ConfirmQuit {
+    fn update(&mut self, _ctx: &mut Ctx) -> Transition {
+        if is_key_pressed(KeyCode::Enter) {
+            Transition::Quit
+        } else if is_key_pressed(KeyCode::Escape) {
+            Transition::Pop
+        } else {
+            Transition::Stay
+        }
+    }
+
+    fn draw(&mut self, _ctx: &Ctx) {
+        draw_text_centered(
+            "Do you want to quit?",
+            screen_width() / 2.,
+            300.,
+            50.,
+            WHITE,
+        );
+        draw_text_centered("<Esc> no", screen_width() / 2., 350., 50., WHITE);
+        draw_text_centered("<Enter> yes", screen_width() / 2., 400., 50., WHITE);
+    }
+}
+
+/// A state to test with that skips the intro and has plenty of everything
+#[cfg(debug_assertions)]
+fn debug_state(assets: &Assets) -> State {
+    use crate::game::{Farm, Item, Location};
+
+    let mut state = State::new(1, &assets.people);
+    state.inventory.add(Item::Seeds, 10);
+    state.inventory.add(Item::CookedPotato, 10000);
+    state.farm = Some(Farm::default());
+    state.map.unlock(Location::Barn);
+    state.map.unlock(Location::Town);
+    state.has_a_cold = true;
+    state.page = 8;
+    state
+}
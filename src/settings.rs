@@ -0,0 +1,111 @@
+use crate::{
+    dialogue::get_num_pressed,
+    scene::{Ctx, Scene, Transition},
+};
+use macroquad::prelude::*;
+
+/// Preferences of the player
+#[derive(Copy, Clone, Debug)]
+pub struct Settings {
+    pub text_speed: TextSpeed,
+    /// Continue to the next page on its own once all the text is visible
+    pub auto_advance: bool,
+    pub typing_sound: bool,
+    /// Instantly show lines that were already read before, also in earlier games
+    pub skip_seen: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            text_speed: TextSpeed::Normal,
+            auto_advance: false,
+            typing_sound: true,
+            skip_seen: false,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextSpeed {
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl TextSpeed {
+    pub fn chars_per_second(&self) -> f32 {
+        match self {
+            Self::Slow => 25.,
+            Self::Normal => 60.,
+            Self::Fast => 120.,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Slow => "slow",
+            Self::Normal => "normal",
+            Self::Fast => "fast",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Self::Slow => Self::Normal,
+            Self::Normal => Self::Fast,
+            Self::Fast => Self::Slow,
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Changing the settings, opened from the pause menu
+pub struct SettingsScreen;
+
+impl Scene for SettingsScreen {
+    fn update(&mut self, ctx: &mut Ctx) -> Transition {
+        let settings = &mut ctx.settings;
+        match get_num_pressed() {
+            Some(1) => settings.text_speed = settings.text_speed.next(),
+            Some(2) => settings.auto_advance = !settings.auto_advance,
+            Some(3) => settings.typing_sound = !settings.typing_sound,
+            Some(4) => settings.skip_seen = !settings.skip_seen,
+            _ => {}
+        }
+
+        if is_key_pressed(KeyCode::Escape) {
+            Transition::Pop
+        } else {
+            Transition::Stay
+        }
+    }
+
+    fn draw(&mut self, ctx: &Ctx) {
+        let settings = ctx.settings;
+        draw_text("Settings", 50., 50., 40., WHITE);
+        let options = [
+            format!("Text speed: {}", settings.text_speed.name()),
+            format!("Auto-advance: {}", on_off(settings.auto_advance)),
+            format!("Typing sound: {}", on_off(settings.typing_sound)),
+            format!("Skip seen text: {}", on_off(settings.skip_seen)),
+        ];
+        for (index, option) in options.iter().enumerate() {
+            draw_text(
+                &format!("<{}> {}", index + 1, option),
+                50.,
+                130. + index as f32 * 40.,
+                30.,
+                WHITE,
+            );
+        }
+        draw_text("<Esc> back", 50., screen_height() - 50., 24., WHITE);
+    }
+}